@@ -0,0 +1,91 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A ready-made comparable value, without defining a trait.
+
+use core::{
+	any::Any,
+	fmt::{self, Debug},
+	ops::{Deref, DerefMut},
+};
+
+use crate::{Box, DynEq};
+
+/// Any comparable value, boxed.
+///
+/// This is a `Box<dyn DynEq + Send + Sync>` that implements [`PartialEq`] and [`Eq`], for
+/// application code that needs to store values of unrelated types without defining its own trait.
+///
+/// There is no `From<T>` implementation for every value, since it would conflict with the
+/// reflexive `From<AnyEq>` implementation; use [`AnyEq::new`] instead.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::AnyEq;
+///
+/// let values = [AnyEq::new(5u8), AnyEq::new("five"), AnyEq::new(5u16)];
+///
+/// assert!(values.contains(&AnyEq::new("five")));
+/// assert!(!values.contains(&AnyEq::new(5u32)));
+/// assert_eq!(values[0].downcast_ref::<u8>(), Some(&5));
+/// ```
+#[derive(PartialEq, Eq)]
+pub struct AnyEq(Box<dyn DynEq + Send + Sync>);
+
+impl AnyEq {
+	/// Boxes `value`.
+	pub fn new<T: Eq + Send + Sync + 'static>(value: T) -> Self {
+		Self(Box::new(value))
+	}
+
+	/// Returns `true` if the concrete type of the value is `T`.
+	pub fn is<T: Any>(&self) -> bool {
+		crate::is::<T>(&self.0)
+	}
+
+	/// Returns a reference to the value if it is of type `T`, or `None` if it isn't.
+	pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		crate::downcast_ref(&self.0)
+	}
+
+	/// Returns a mutable reference to the value if it is of type `T`, or `None` if it isn't.
+	pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+		crate::downcast_mut(&mut self.0)
+	}
+
+	/// Returns the boxed trait object.
+	pub fn into_inner(self) -> Box<dyn DynEq + Send + Sync> {
+		self.0
+	}
+}
+
+impl From<Box<dyn DynEq + Send + Sync>> for AnyEq {
+	fn from(value: Box<dyn DynEq + Send + Sync>) -> Self {
+		Self(value)
+	}
+}
+
+impl Deref for AnyEq {
+	type Target = dyn DynEq + Send + Sync;
+
+	fn deref(&self) -> &Self::Target {
+		&*self.0
+	}
+}
+
+impl DerefMut for AnyEq {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut *self.0
+	}
+}
+
+impl Debug for AnyEq {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("AnyEq")
+			.field(&crate::__private::type_description(&*self.0))
+			.finish()
+	}
+}
@@ -0,0 +1,258 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Assertion macros for trait objects.
+
+#[cfg(feature = "pretty-diff")]
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(feature = "pretty-diff")]
+use core::fmt::Write;
+use core::fmt::{self, Debug};
+
+use crate::DynEq;
+
+/// Asserts that two trait objects are equal.
+///
+/// Unlike [`assert_eq`], the values don't need to implement [`Debug`]. On failure, the message
+/// includes the concrete type of both values (their name if the `type-name` feature is enabled,
+/// their [`TypeId`](core::any::TypeId) otherwise) and their [`Debug`] representation if the type
+/// of the expressions implements it.
+///
+/// With the `pretty-diff` feature, the failure message instead shows a colored line by line
+/// difference between the pretty-printed values.
+///
+/// Boxes must be dereferenced (`assert_dyn_eq!(*a, *b)`), otherwise the reported type is the
+/// type of the box.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+/// dyn_eq::eq_trait_object!(MyTrait);
+///
+/// impl MyTrait for u8 {}
+///
+/// let a: Box<dyn MyTrait> = Box::new(5u8);
+/// let b: Box<dyn MyTrait> = Box::new(5u8);
+/// dyn_eq::assert_dyn_eq!(*a, *b);
+/// dyn_eq::assert_dyn_eq!(*a, *b, "values should be equal");
+/// ```
+#[macro_export]
+macro_rules! assert_dyn_eq {
+	($left:expr, $right:expr $(,)?) => {
+		$crate::__internal_assert_dyn!(== (!) $left, $right, ::core::option::Option::None)
+	};
+	($left:expr, $right:expr, $($arg:tt)+) => {
+		$crate::__internal_assert_dyn!(== (!) $left, $right, ::core::option::Option::Some(::core::format_args!($($arg)+)))
+	};
+}
+
+/// Asserts that two trait objects are not equal.
+///
+/// See [`assert_dyn_eq`] for the content of the failure message.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+/// dyn_eq::eq_trait_object!(MyTrait);
+///
+/// impl MyTrait for u8 {}
+/// impl MyTrait for u16 {}
+///
+/// let a: &dyn MyTrait = &5u8;
+/// let b: &dyn MyTrait = &5u16;
+/// dyn_eq::assert_dyn_ne!(*a, *b);
+/// ```
+#[macro_export]
+macro_rules! assert_dyn_ne {
+	($left:expr, $right:expr $(,)?) => {
+		$crate::__internal_assert_dyn!(!= () $left, $right, ::core::option::Option::None)
+	};
+	($left:expr, $right:expr, $($arg:tt)+) => {
+		$crate::__internal_assert_dyn!(!= () $left, $right, ::core::option::Option::Some(::core::format_args!($($arg)+)))
+	};
+}
+
+/// Internal implementation of [`assert_dyn_eq`] and [`assert_dyn_ne`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_assert_dyn {
+	($op:tt ($($not:tt)?) $left:expr, $right:expr, $args:expr) => {
+		match (&$left, &$right) {
+			(left, right) => {
+				if $($not)?(*left == *right) {
+					#[allow(unused_imports)]
+					use $crate::__private::{DebugFallback as _, DebugSpecialized as _};
+
+					$crate::__private::assert_failed(
+						::core::stringify!($op),
+						&(&$crate::__private::DebugWrap(left)).dyn_eq_debug(),
+						&$crate::__private::type_description(left),
+						&(&$crate::__private::DebugWrap(right)).dyn_eq_debug(),
+						&$crate::__private::type_description(right),
+						$args,
+					);
+				}
+			}
+		}
+	};
+}
+
+/// Wrapper used to find out if a value implements [`Debug`], using autoref specialization.
+pub struct DebugWrap<'a, T: ?Sized>(pub &'a T);
+
+/// Used when the value implements [`Debug`].
+pub trait DebugSpecialized {
+	/// Returns something printing the value.
+	fn dyn_eq_debug(&self) -> impl Debug + '_;
+}
+
+impl<T: ?Sized + Debug> DebugSpecialized for DebugWrap<'_, T> {
+	fn dyn_eq_debug(&self) -> impl Debug + '_ {
+		self.0
+	}
+}
+
+/// Used when the value doesn't implement [`Debug`].
+pub trait DebugFallback {
+	/// Returns something saying that the value can't be printed.
+	fn dyn_eq_debug(&self) -> impl Debug + '_;
+}
+
+impl<T: ?Sized> DebugFallback for &DebugWrap<'_, T> {
+	fn dyn_eq_debug(&self) -> impl Debug + '_ {
+		NoDebug
+	}
+}
+
+/// Printed in place of values that don't implement [`Debug`].
+struct NoDebug;
+
+impl Debug for NoDebug {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("<no Debug implementation>")
+	}
+}
+
+/// Describes the concrete type of a value.
+#[cfg(feature = "type-name")]
+pub fn type_description(value: &(impl ?Sized + DynEq)) -> impl Debug {
+	/// Printed without quotes, unlike a `&str`.
+	struct TypeName(&'static str);
+
+	impl Debug for TypeName {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			f.write_str(self.0)
+		}
+	}
+
+	TypeName(value.dyn_type_name())
+}
+
+/// Describes the concrete type of a value.
+#[cfg(not(feature = "type-name"))]
+pub fn type_description(value: &(impl ?Sized + DynEq)) -> impl Debug {
+	value.dyn_type_id()
+}
+
+/// Panics with the message of a failed assertion.
+#[cold]
+#[track_caller]
+pub fn assert_failed(
+	op: &str,
+	left: &dyn Debug,
+	left_type: &dyn Debug,
+	right: &dyn Debug,
+	right_type: &dyn Debug,
+	args: Option<fmt::Arguments<'_>>,
+) -> ! {
+	#[cfg(feature = "pretty-diff")]
+	if op == "==" {
+		diff_failed(left, left_type, right, right_type, args);
+	}
+
+	match args {
+		Some(args) => panic!(
+			"assertion `left {op} right` failed: {args}\n  left: {left:?} (type {left_type:?})\n \
+			 right: {right:?} (type {right_type:?})"
+		),
+		None => panic!(
+			"assertion `left {op} right` failed\n  left: {left:?} (type {left_type:?})\n right: \
+			 {right:?} (type {right_type:?})"
+		),
+	}
+}
+
+/// Panics with the message of a failed [`assert_dyn_eq`], showing the difference between the
+/// values.
+#[cfg(feature = "pretty-diff")]
+#[cold]
+#[track_caller]
+fn diff_failed(
+	left: &dyn Debug,
+	left_type: &dyn Debug,
+	right: &dyn Debug,
+	right_type: &dyn Debug,
+	args: Option<fmt::Arguments<'_>>,
+) -> ! {
+	let diff = line_diff(&format!("{left:#?}"), &format!("{right:#?}"));
+	match args {
+		Some(args) => panic!(
+			"assertion `left == right` failed: {args}\n  left type: {left_type:?}\n right type: \
+			 {right_type:?}\n\nDiff < left / > right :\n{diff}"
+		),
+		None => panic!(
+			"assertion `left == right` failed\n  left type: {left_type:?}\n right type: \
+			 {right_type:?}\n\nDiff < left / > right :\n{diff}"
+		),
+	}
+}
+
+/// Returns the lines of `left` and `right`, with the removed lines in red and prefixed by `<`, and
+/// the added lines in green and prefixed by `>`.
+#[cfg(feature = "pretty-diff")]
+fn line_diff(left: &str, right: &str) -> String {
+	let left: Vec<&str> = left.lines().collect();
+	let right: Vec<&str> = right.lines().collect();
+
+	// `lengths[i * width + j]` is the length of the longest common subsequence of `left[i..]` and
+	// `right[j..]`
+	let width = right.len() + 1;
+	let mut lengths = vec![0usize; (left.len() + 1) * width];
+	for i in (0..left.len()).rev() {
+		for j in (0..right.len()).rev() {
+			lengths[i * width + j] = if left[i] == right[j] {
+				lengths[(i + 1) * width + j + 1] + 1
+			} else {
+				lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+			};
+		}
+	}
+
+	let mut diff = String::new();
+	let (mut i, mut j) = (0, 0);
+	// Writing to a `String` can't fail
+	while i < left.len() || j < right.len() {
+		if i < left.len() && j < right.len() && left[i] == right[j] {
+			let _ = writeln!(diff, " {}", left[i]);
+			i += 1;
+			j += 1;
+		} else if j == right.len()
+			|| (i < left.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+		{
+			let _ = writeln!(diff, "\x1b[31m<{}\x1b[0m", left[i]);
+			i += 1;
+		} else {
+			let _ = writeln!(diff, "\x1b[32m>{}\x1b[0m", right[j]);
+			j += 1;
+		}
+	}
+	diff
+}
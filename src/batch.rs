@@ -0,0 +1,117 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers to compare many trait objects at once.
+//!
+//! Values are first sorted into buckets by concrete type, then only compared with values of the
+//! same bucket. Values of different types are never compared, and the comparisons inside of a
+//! bucket never fail to downcast.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::DynEq;
+
+/// Groups the elements of the slice that are equal, and returns the indices of each group.
+///
+/// The indices of each group are in increasing order, and the groups are sorted by their first
+/// index, so the result doesn't depend on the order of the [`TypeId`](core::any::TypeId)s.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Event: DynEq {}
+/// impl Event for u8 {}
+/// impl Event for u16 {}
+///
+/// let events: Vec<Box<dyn Event>> =
+///     vec![Box::new(1u8), Box::new(1u16), Box::new(2u8), Box::new(1u8)];
+///
+/// assert_eq!(
+///     dyn_eq::batch::group_eq(&events),
+///     [vec![0, 3], vec![1], vec![2]]
+/// );
+/// ```
+pub fn group_eq<P>(slice: &[P]) -> Vec<Vec<usize>>
+where
+	P: Deref,
+	P::Target: DynEq,
+{
+	let mut indices: Vec<usize> = (0..slice.len()).collect();
+	// The sort is stable, so the indices stay in increasing order inside of each bucket
+	indices.sort_by_key(|&i| (*slice[i]).dyn_type_id());
+
+	let mut groups: Vec<Vec<usize>> = Vec::new();
+	for bucket in indices.chunk_by(|&a, &b| (*slice[a]).dyn_type_id() == (*slice[b]).dyn_type_id())
+	{
+		let start = groups.len();
+		for &i in bucket {
+			let value = &*slice[i];
+			match groups[start..]
+				.iter_mut()
+				.find(|group| (*slice[group[0]]).dyn_eq(value.as_any()))
+			{
+				Some(group) => group.push(i),
+				None => groups.push(Vec::from([i])),
+			}
+		}
+	}
+
+	groups.sort_unstable_by_key(|group| group[0]);
+	groups
+}
+
+/// Partitions the values into groups of equal values.
+///
+/// The values of each group are in their original order, and the groups are sorted by their
+/// first value, like for [`group_eq`].
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Event: DynEq {}
+/// dyn_eq::eq_trait_object!(Event);
+///
+/// impl Event for u8 {}
+/// impl Event for u16 {}
+///
+/// let events: Vec<Box<dyn Event>> =
+///     vec![Box::new(1u8), Box::new(1u16), Box::new(2u8), Box::new(1u8)];
+///
+/// let groups = dyn_eq::batch::group(events);
+/// let lengths: Vec<usize> = groups.iter().map(Vec::len).collect();
+/// assert_eq!(lengths, [2, 1, 1]);
+/// assert!(groups[0][0] == groups[0][1]);
+/// ```
+pub fn group<P>(values: Vec<P>) -> Vec<Vec<P>>
+where
+	P: Deref,
+	P::Target: DynEq,
+{
+	group_iter(values).collect()
+}
+
+/// Partitions the values into groups of equal values, and returns an iterator over the groups.
+///
+/// The comparisons are all done before this function returns, the iterator only moves the values
+/// into their group. The groups are in the same order as for [`group`].
+pub fn group_iter<P>(values: Vec<P>) -> impl Iterator<Item = Vec<P>>
+where
+	P: Deref,
+	P::Target: DynEq,
+{
+	let groups = group_eq(&values);
+	let mut values: Vec<Option<P>> = values.into_iter().map(Some).collect();
+	groups.into_iter().map(move |group| {
+		group
+			.into_iter()
+			.map(|i| values[i].take().expect("each index is in a single group"))
+			.collect()
+	})
+}
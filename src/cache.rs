@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Memoization of expensive comparisons.
+
+use alloc::collections::BTreeMap;
+use core::any::TypeId;
+
+use crate::DynEq;
+
+/// The identity of a value: its address and its concrete type.
+///
+/// The type is needed because different values can share the same address, such as a struct and
+/// its first field, or zero-sized values.
+type Key = (usize, TypeId);
+
+/// A cache of the results of comparisons between values, keyed by their addresses.
+///
+/// This is useful when the same values are compared many times and their [`Eq`] implementation
+/// is expensive. Comparisons between values of different types are never cached, since they
+/// don't call [`Eq`], and a value is always equal to itself.
+///
+/// The cache doesn't borrow the values, so it can't know when they change: after a value is
+/// mutated, moved or dropped, [`invalidate`](Self::invalidate) it (or [`clear`](Self::clear) the
+/// whole cache), otherwise the cache may return outdated results.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynEq, EqCache};
+///
+/// trait Constraint: DynEq {}
+/// impl Constraint for Vec<u8> {}
+///
+/// let mut a: Box<dyn Constraint> = Box::new(vec![0u8; 1024]);
+/// let b: Box<dyn Constraint> = Box::new(vec![0u8; 1024]);
+///
+/// let mut cache = EqCache::new();
+/// assert!(cache.eq(&*a, &*b));
+/// // Doesn't compare the vectors again
+/// assert!(cache.eq(&*b, &*a));
+///
+/// dyn_eq::downcast_mut::<Vec<u8>>(&mut a).unwrap()[0] = 1;
+/// cache.invalidate(&*a);
+/// assert!(!cache.eq(&*a, &*b));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct EqCache {
+	/// The results, the smallest key of each pair comes first.
+	results: BTreeMap<(Key, Key), bool>,
+}
+
+impl EqCache {
+	/// Creates an empty cache.
+	pub const fn new() -> Self {
+		Self {
+			results: BTreeMap::new(),
+		}
+	}
+
+	/// Tests if `a` and `b` are of the same type and equal, using the result of a previous
+	/// comparison between them if there is one.
+	pub fn eq(&mut self, a: &(impl ?Sized + DynEq), b: &(impl ?Sized + DynEq)) -> bool {
+		let (a_key, b_key) = (key(a), key(b));
+		if a_key.1 != b_key.1 {
+			return false;
+		}
+		if a_key == b_key {
+			return true;
+		}
+
+		let pair = if a_key < b_key {
+			(a_key, b_key)
+		} else {
+			(b_key, a_key)
+		};
+		*self.results.entry(pair).or_insert_with(|| crate::eq(a, b))
+	}
+
+	/// Tests if `a` and `b` are of different types or not equal, using the result of a previous
+	/// comparison between them if there is one.
+	pub fn ne(&mut self, a: &(impl ?Sized + DynEq), b: &(impl ?Sized + DynEq)) -> bool {
+		!self.eq(a, b)
+	}
+
+	/// Forgets the results of every comparison involving `value`.
+	pub fn invalidate(&mut self, value: &(impl ?Sized + DynEq)) {
+		let value = key(value);
+		self.results.retain(|&(a, b), _| a != value && b != value);
+	}
+
+	/// Forgets the results of every comparison.
+	pub fn clear(&mut self) {
+		self.results.clear();
+	}
+
+	/// Returns the number of cached results.
+	pub fn len(&self) -> usize {
+		self.results.len()
+	}
+
+	/// Returns `true` if no result is cached.
+	pub fn is_empty(&self) -> bool {
+		self.results.is_empty()
+	}
+}
+
+/// Returns the identity of `value`.
+fn key(value: &(impl ?Sized + DynEq)) -> Key {
+	(
+		(value as *const _ as *const ()) as usize,
+		value.dyn_type_id(),
+	)
+}
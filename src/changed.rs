@@ -0,0 +1,96 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Change detection for values compared through trait objects.
+
+use core::ops::Deref;
+
+use crate::DynEq;
+
+/// A value that reports whether it changed when it is replaced.
+///
+/// This works on any [`PartialEq`] value, which includes `Box<dyn Trait>` once
+/// [`eq_trait_object`](crate::eq_trait_object) has been invoked for `Trait`. The value can be
+/// read through [`Deref`], but not mutated in place, since the change couldn't be detected.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{Changed, DynEq};
+///
+/// trait State: DynEq {}
+/// dyn_eq::eq_trait_object!(State);
+///
+/// impl State for u8 {}
+/// impl State for u16 {}
+///
+/// let mut state: Changed<Box<dyn State>> = Changed::new(Box::new(1u8));
+///
+/// assert!(!state.set(Box::new(1u8)));
+/// assert!(state.set(Box::new(1u16)));
+/// assert!(state.set(Box::new(2u16)));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Changed<T> {
+	/// The current value.
+	value: T,
+}
+
+impl<T> Changed<T> {
+	/// Wraps the initial value.
+	pub const fn new(value: T) -> Self {
+		Self { value }
+	}
+
+	/// Returns the current value.
+	pub fn into_inner(self) -> T {
+		self.value
+	}
+}
+
+impl<T: PartialEq> Changed<T> {
+	/// Replaces the value, and returns `true` if the new one isn't equal to the previous one.
+	///
+	/// The new value is stored even if it is equal to the previous one.
+	pub fn set(&mut self, value: T) -> bool {
+		let changed = self.value != value;
+		self.value = value;
+		changed
+	}
+}
+
+impl<P> Changed<P>
+where
+	P: Deref,
+	P::Target: DynEq + PartialEq,
+{
+	/// Like [`set`](Self::set), but a new value pointing to the same address as the previous one,
+	/// with the same concrete type, is unchanged without being compared. This speeds up the
+	/// replacement of shared values, such as `Rc<dyn Trait>` or `&'static dyn Trait`.
+	///
+	/// Pointers are only compared by address, so this must not be used with pointers whose
+	/// pointee can be mutated in place, such as `Rc<RefCell<dyn Trait>>`.
+	pub fn set_ptr(&mut self, value: P) -> bool {
+		let (old, new) = (&*self.value, &*value);
+		let same = core::ptr::addr_eq(old, new) && old.dyn_type_id() == new.dyn_type_id();
+		let changed = !same && old != new;
+		self.value = value;
+		changed
+	}
+}
+
+impl<T> Deref for Changed<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.value
+	}
+}
+
+impl<T> From<T> for Changed<T> {
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Comparison of values implementing [`DynEq`], without the need for [`eq_trait_object`].
+//!
+//! A box implements [`DynEq`] itself once [`PartialEq`] is implemented for it, so comparing a box
+//! with a value that isn't a box is always `false`. The content of a box is compared with a
+//! concrete value by [`DynEqExt::eq_concrete`].
+//!
+//! [`eq_trait_object`]: crate::eq_trait_object
+//! [`DynEqExt::eq_concrete`]: crate::DynEqExt::eq_concrete
+
+use crate::DynEq;
+
+/// Tests if `a` and `b` are of the same type and equal.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// let a: &dyn DynEq = &5u8;
+/// let b: &dyn DynEq = &5u16;
+///
+/// assert!(dyn_eq::eq(a, &5u8));
+/// assert!(!dyn_eq::eq(a, b));
+/// ```
+#[inline]
+pub fn eq(a: &(impl ?Sized + DynEq), b: &(impl ?Sized + DynEq)) -> bool {
+	#[cfg(feature = "unstable-specialization")]
+	return SpecEq::spec_eq(a, b);
+	#[cfg(not(feature = "unstable-specialization"))]
+	return a.dyn_eq(b.as_any());
+}
+
+/// Tests if `a` and `b` are of different types or not equal.
+#[inline]
+pub fn ne(a: &(impl ?Sized + DynEq), b: &(impl ?Sized + DynEq)) -> bool {
+	!eq(a, b)
+}
+
+/// Tests if `a` and `b` have equal keys, as returned by `key`.
+///
+/// This compares values through a projection, such as an identifier, without needing a wrapper
+/// type. Unlike [`eq`], the values don't need to implement [`DynEq`].
+///
+/// # Example
+///
+/// ```
+/// trait Command {
+///     fn id(&self) -> &str;
+/// }
+///
+/// struct Move(&'static str, u32);
+/// struct Jump(&'static str);
+///
+/// impl Command for Move {
+///     fn id(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// impl Command for Jump {
+///     fn id(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// let a: &dyn Command = &Move("a", 5);
+/// let b: &dyn Command = &Jump("a");
+///
+/// assert!(dyn_eq::eq_by_key(a, b, |command| command.id()));
+/// ```
+#[inline]
+pub fn eq_by_key<'a, T: ?Sized, K: PartialEq>(
+	a: &'a T,
+	b: &'a T,
+	key: impl Fn(&'a T) -> K,
+) -> bool {
+	key(a) == key(b)
+}
+
+/// Comparison that skips the [`Any`](core::any::Any) round-trip when both values are known to be
+/// of the same type at compile time.
+#[cfg(feature = "unstable-specialization")]
+pub(crate) trait SpecEq<U: ?Sized> {
+	/// Tests if `self` and `other` are of the same type and equal.
+	fn spec_eq(&self, other: &U) -> bool;
+}
+
+#[cfg(feature = "unstable-specialization")]
+impl<T: ?Sized + DynEq, U: ?Sized + DynEq> SpecEq<U> for T {
+	#[inline]
+	default fn spec_eq(&self, other: &U) -> bool {
+		self.dyn_eq(other.as_any())
+	}
+}
+
+#[cfg(feature = "unstable-specialization")]
+impl<T: Eq + 'static> SpecEq<T> for T {
+	#[inline]
+	fn spec_eq(&self, other: &T) -> bool {
+		self == other
+	}
+}
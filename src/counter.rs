@@ -0,0 +1,132 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Counting of equal trait objects.
+
+use alloc::vec::Vec;
+
+use crate::Box;
+
+/// A multiset of boxed values, counting how many times each distinct value was added.
+///
+/// This works on any [`PartialEq`] type, which includes `dyn Trait` once
+/// [`eq_trait_object`](crate::eq_trait_object) has been invoked for `Trait`. Since trait objects
+/// can't be hashed, each added value is compared with every distinct value, so adding `n` values
+/// is quadratic in the worst case.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynCounter, DynEq};
+///
+/// trait Event: DynEq {}
+/// dyn_eq::eq_trait_object!(Event);
+///
+/// impl Event for u8 {}
+/// impl Event for u16 {}
+///
+/// let mut counter: DynCounter<dyn Event> = DynCounter::new();
+/// counter.add(Box::new(1u8));
+/// counter.add(Box::new(1u16));
+/// counter.add(Box::new(1u8));
+///
+/// assert_eq!(counter.count_of(&1u8), 2);
+/// assert_eq!(counter.count_of(&1u16), 1);
+/// assert_eq!(counter.count_of(&2u8), 0);
+///
+/// let (value, count) = counter.most_common()[0];
+/// assert!(value == &1u8 as &dyn Event);
+/// assert_eq!(count, 2);
+/// ```
+#[derive(Debug)]
+pub struct DynCounter<T: ?Sized> {
+	/// The distinct values with their count, in the order in which they were first added.
+	entries: Vec<(Box<T>, usize)>,
+}
+
+impl<T: ?Sized> DynCounter<T> {
+	/// Creates an empty counter.
+	pub const fn new() -> Self {
+		Self {
+			entries: Vec::new(),
+		}
+	}
+
+	/// Returns the number of distinct values.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns `true` if no value was added.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the total number of added values, including the duplicates.
+	pub fn total(&self) -> usize {
+		self.entries.iter().map(|(_, count)| count).sum()
+	}
+
+	/// Iterates over the distinct values and their count, in the order in which they were first
+	/// added.
+	pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+		self.entries.iter().map(|(value, count)| (&**value, *count))
+	}
+
+	/// Returns the distinct values and their count, from the most common to the least common.
+	///
+	/// Values with the same count are in the order in which they were first added.
+	pub fn most_common(&self) -> Vec<(&T, usize)> {
+		let mut entries: Vec<_> = self.iter().collect();
+		entries.sort_by(|(_, a), (_, b)| b.cmp(a));
+		entries
+	}
+}
+
+impl<T: ?Sized + PartialEq> DynCounter<T> {
+	/// Adds a value, and returns the number of times it has been added.
+	pub fn add(&mut self, value: Box<T>) -> usize {
+		match self.entries.iter_mut().find(|(other, _)| **other == *value) {
+			Some((_, count)) => {
+				*count += 1;
+				*count
+			}
+			None => {
+				self.entries.push((value, 1));
+				1
+			}
+		}
+	}
+
+	/// Returns the number of times a value equal to `value` has been added.
+	pub fn count_of(&self, value: &T) -> usize {
+		self.entries
+			.iter()
+			.find(|(other, _)| **other == *value)
+			.map_or(0, |(_, count)| *count)
+	}
+}
+
+impl<T: ?Sized> Default for DynCounter<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: ?Sized + PartialEq> Extend<Box<T>> for DynCounter<T> {
+	fn extend<I: IntoIterator<Item = Box<T>>>(&mut self, iter: I) {
+		for value in iter {
+			self.add(value);
+		}
+	}
+}
+
+impl<T: ?Sized + PartialEq> FromIterator<Box<T>> for DynCounter<T> {
+	fn from_iter<I: IntoIterator<Item = Box<T>>>(iter: I) -> Self {
+		let mut counter = Self::new();
+		counter.extend(iter);
+		counter
+	}
+}
@@ -0,0 +1,20 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Equality between values of different types.
+
+/// Equality between values of two different types, used by the `cross_type(...)` option of
+/// [`eq_trait_object`](crate::eq_trait_object).
+///
+/// By default, trait objects with different concrete types are never equal. Implementing this
+/// trait allows two types representing the same thing (e.g. two units, or two versions of a
+/// configuration) to be equal when compared through trait objects.
+///
+/// Like [`Eq`], the comparison must be consistent: the implementation is used in both directions,
+/// and two values equal to a third one should be equal to each other.
+pub trait CrossTypeEq<Other: ?Sized> {
+	/// Tests if `self` and `other` represent the same value.
+	fn cross_eq(&self, other: &Other) -> bool;
+}
@@ -0,0 +1,168 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Structured reports of why two trait objects are different.
+
+use core::{
+	any::TypeId,
+	fmt::{self, Debug, Display},
+	ops::Deref,
+};
+
+use crate::{DynEq, assert::type_description};
+
+/// The reason why two values are different, as returned by [`diff`].
+///
+/// If the values implement [`Debug`], the report can be printed with [`Display`]. The concrete
+/// types are described by their name if the `type-name` feature is enabled, and by their
+/// [`TypeId`](core::any::TypeId) otherwise.
+#[derive(Debug)]
+pub enum DynDifference<'a, T: ?Sized> {
+	/// The values have different concrete types.
+	Type {
+		/// The left value.
+		left: &'a T,
+		/// The right value.
+		right: &'a T,
+	},
+	/// The values have the same concrete type, but aren't equal.
+	Value {
+		/// The left value.
+		left: &'a T,
+		/// The right value.
+		right: &'a T,
+	},
+}
+
+/// The result of comparing two values, as returned by [`compare`].
+///
+/// Unlike the boolean result of [`PartialEq`], this distinguishes values of different concrete
+/// types from values of the same type that aren't equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DynComparison {
+	/// The values have different concrete types.
+	DifferentType {
+		/// The concrete type of the left value.
+		left: TypeId,
+		/// The concrete type of the right value.
+		right: TypeId,
+	},
+	/// The values have the same concrete type and are equal.
+	Equal,
+	/// The values have the same concrete type, but aren't equal.
+	NotEqual,
+}
+
+impl DynComparison {
+	/// Returns `true` if the values are equal.
+	pub const fn is_eq(self) -> bool {
+		matches!(self, Self::Equal)
+	}
+}
+
+impl<T: ?Sized + DynEq + Debug> Display for DynDifference<'_, T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Type { left, right } => write!(
+				f,
+				"different types\n  left: {left:?} (type {:?})\n right: {right:?} (type {:?})",
+				type_description(*left),
+				type_description(*right),
+			),
+			Self::Value { left, right } => write!(
+				f,
+				"different values of type {:?}\n  left: {left:?}\n right: {right:?}",
+				type_description(*left),
+			),
+		}
+	}
+}
+
+/// Compares the values pointed to by `left` and `right`, and returns why they are different, or
+/// `None` if they are equal.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynDifference, DynEq};
+///
+/// trait MyTrait: DynEq + std::fmt::Debug {}
+/// impl MyTrait for u8 {}
+/// impl MyTrait for u16 {}
+///
+/// let a: Box<dyn MyTrait> = Box::new(5u8);
+/// let b: Box<dyn MyTrait> = Box::new(6u8);
+/// let c: Box<dyn MyTrait> = Box::new(5u16);
+///
+/// assert!(dyn_eq::diff(&a, &a).is_none());
+/// assert!(matches!(
+///     dyn_eq::diff(&a, &b),
+///     Some(DynDifference::Value { .. })
+/// ));
+/// assert!(matches!(
+///     dyn_eq::diff(&a, &c),
+///     Some(DynDifference::Type { .. })
+/// ));
+///
+/// let report = dyn_eq::diff(&a, &b).unwrap().to_string();
+/// assert!(report.contains("left: 5") && report.contains("right: 6"));
+/// ```
+pub fn diff<'a, P>(left: &'a P, right: &'a P) -> Option<DynDifference<'a, P::Target>>
+where
+	P: Deref,
+	P::Target: DynEq,
+{
+	let (left, right) = (&**left, &**right);
+	if left.dyn_type_id() != right.dyn_type_id() {
+		Some(DynDifference::Type { left, right })
+	} else if left.dyn_eq(right.as_any()) {
+		None
+	} else {
+		Some(DynDifference::Value { left, right })
+	}
+}
+
+/// Compares the values pointed to by `left` and `right`, telling apart values of different types
+/// from values that aren't equal.
+///
+/// # Example
+///
+/// ```
+/// use core::any::TypeId;
+///
+/// use dyn_eq::{DynComparison, DynEq};
+///
+/// trait MyTrait: DynEq {}
+/// impl MyTrait for u8 {}
+/// impl MyTrait for u16 {}
+///
+/// let a: &dyn MyTrait = &5u8;
+/// let b: &dyn MyTrait = &6u8;
+/// let c: &dyn MyTrait = &5u16;
+///
+/// assert_eq!(dyn_eq::compare(&a, &a), DynComparison::Equal);
+/// assert_eq!(dyn_eq::compare(&a, &b), DynComparison::NotEqual);
+/// assert_eq!(
+///     dyn_eq::compare(&a, &c),
+///     DynComparison::DifferentType {
+///         left: TypeId::of::<u8>(),
+///         right: TypeId::of::<u16>(),
+///     }
+/// );
+/// ```
+pub fn compare<P>(left: &P, right: &P) -> DynComparison
+where
+	P: Deref,
+	P::Target: DynEq,
+{
+	match diff(left, right) {
+		None => DynComparison::Equal,
+		Some(DynDifference::Value { .. }) => DynComparison::NotEqual,
+		Some(DynDifference::Type { left, right }) => DynComparison::DifferentType {
+			left: left.dyn_type_id(),
+			right: right.dyn_type_id(),
+		},
+	}
+}
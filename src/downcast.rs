@@ -0,0 +1,66 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers to downcast trait objects that have [`DynEq`] as a supertrait.
+//!
+//! These functions take a pointer to the trait object, such as `&dyn Trait` or `Box<dyn Trait>`,
+//! by reference.
+
+use core::{
+	any::Any,
+	ops::{Deref, DerefMut},
+};
+
+#[cfg(feature = "alloc")]
+use crate::Box;
+use crate::DynEq;
+
+/// Returns `true` if the concrete type of the value pointed to by `value` is `T`.
+pub fn is<T: Any>(value: &impl Deref<Target = impl ?Sized + DynEq>) -> bool {
+	(**value).as_any().is::<T>()
+}
+
+/// Returns a reference to the value pointed to by `value` if it is of type `T`, or `None` if it
+/// isn't.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+/// impl MyTrait for u8 {}
+///
+/// let value: &dyn MyTrait = &5u8;
+/// assert_eq!(dyn_eq::downcast_ref::<u8>(&value), Some(&5));
+/// assert_eq!(dyn_eq::downcast_ref::<u16>(&value), None);
+///
+/// let boxed: Box<dyn MyTrait> = Box::new(5u8);
+/// assert_eq!(dyn_eq::downcast_ref::<u8>(&boxed), Some(&5));
+/// ```
+pub fn downcast_ref<T: Any>(value: &impl Deref<Target = impl ?Sized + DynEq>) -> Option<&T> {
+	(**value).as_any().downcast_ref()
+}
+
+/// Returns a mutable reference to the value pointed to by `value` if it is of type `T`, or
+/// `None` if it isn't.
+pub fn downcast_mut<T: Any>(
+	value: &mut impl DerefMut<Target = impl ?Sized + DynEq>,
+) -> Option<&mut T> {
+	(**value).as_any_mut().downcast_mut()
+}
+
+/// Downcasts the box to the concrete type, or gives it back if it isn't of type `T`.
+#[cfg(feature = "alloc")]
+pub fn downcast<T: Any, U: ?Sized + DynEq>(value: Box<U>) -> Result<Box<T>, Box<U>> {
+	if (*value).as_any().is::<T>() {
+		Ok(value
+			.into_any()
+			.downcast()
+			.unwrap_or_else(|_| unreachable!("the type has already been checked")))
+	} else {
+		Err(value)
+	}
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Comparable error trait objects.
+
+use core::error::Error;
+
+use crate::DynEq;
+
+/// An [`Error`] that can be compared, implemented by every error implementing [`Eq`].
+///
+/// [`eq_trait_object`](crate::eq_trait_object) is already invoked for it, so `dyn DynError` and
+/// `Box<dyn DynError>` (with any combination of `Send` and `Sync`) can be compared directly.
+///
+/// # Example
+///
+/// ```
+/// use core::fmt;
+///
+/// use dyn_eq::DynError;
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct NotFound(String);
+///
+/// impl fmt::Display for NotFound {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{} not found", self.0)
+///     }
+/// }
+///
+/// impl std::error::Error for NotFound {}
+///
+/// fn find(name: &str) -> Result<(), Box<dyn DynError>> {
+///     Err(Box::new(NotFound(name.to_owned())))
+/// }
+///
+/// assert_eq!(
+///     find("key").unwrap_err(),
+///     Box::new(NotFound("key".to_owned())) as Box<dyn DynError>
+/// );
+/// ```
+pub trait DynError: Error + DynEq {}
+
+impl<T: Error + Eq + 'static> DynError for T {}
+
+#[allow(unused_parens)]
+const _: () = {
+	crate::eq_trait_object!(DynError);
+};
@@ -0,0 +1,73 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Extension trait for trait objects that have [`DynEq`] as a supertrait.
+
+use core::any::Any;
+
+use crate::DynEq;
+
+/// Extension methods for the trait objects given to [`eq_trait_object`](crate::eq_trait_object),
+/// including `dyn DynEq`.
+///
+/// Boxes and other pointers to these trait objects also implement [`DynEq`] once
+/// [`PartialEq`] is implemented for them, but not this trait, so its methods can be called on the
+/// pointers and always apply to the value they point to.
+pub trait DynEqExt: DynEq {
+	/// Tests if this value is of type `T` and equal to `other`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use dyn_eq::{DynEq, DynEqExt};
+	///
+	/// trait MyTrait: DynEq {}
+	/// impl MyTrait for u8 {}
+	///
+	/// dyn_eq::eq_trait_object!(MyTrait);
+	///
+	/// let value: Box<dyn MyTrait> = Box::new(5u8);
+	/// assert!(value.eq_concrete(&5u8));
+	/// assert!(!value.eq_concrete(&6u8));
+	/// assert!(!value.eq_concrete(&5u16));
+	/// ```
+	fn eq_concrete<T: Eq + 'static>(&self, other: &T) -> bool {
+		crate::eq(self, other)
+	}
+
+	/// Returns `true` if the concrete type of this value is `T`.
+	fn is<T: Any>(&self) -> bool {
+		self.as_any().is::<T>()
+	}
+
+	/// Returns a reference to the concrete value if it is of type `T`, or `None` if it isn't.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use dyn_eq::{DynEq, DynEqExt};
+	///
+	/// trait MyTrait: DynEq {}
+	/// dyn_eq::eq_trait_object!(MyTrait);
+	/// impl MyTrait for u8 {}
+	///
+	/// let value: &dyn MyTrait = &5u8;
+	/// if value.is::<u8>() {
+	///     assert_eq!(value.downcast_ref::<u8>(), Some(&5));
+	/// }
+	/// assert_eq!(value.downcast_ref::<u16>(), None);
+	/// ```
+	fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		self.as_any().downcast_ref()
+	}
+
+	/// Returns a mutable reference to the concrete value if it is of type `T`, or `None` if it
+	/// isn't.
+	fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+		self.as_any_mut().downcast_mut()
+	}
+}
+
+impl<T: ?Sized + DynEq + crate::__private::TraitObject> DynEqExt for T {}
@@ -0,0 +1,126 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Interning of trait objects.
+
+use alloc::vec::Vec;
+
+use crate::Box;
+
+/// The identifier of a value interned by a [`DynInterner`].
+///
+/// Two identifiers returned by the same interner are equal if and only if their values are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternId(usize);
+
+impl InternId {
+	/// Returns the position of the value in the interner, in the order in which the values were
+	/// first interned.
+	pub const fn index(self) -> usize {
+		self.0
+	}
+}
+
+/// A set of boxed values that gives the same [`InternId`] to equal values.
+///
+/// Once interned, values are compared by comparing their identifiers, which is a single integer
+/// comparison. This works on any [`PartialEq`] type, which includes `dyn Trait` once
+/// [`eq_trait_object`](crate::eq_trait_object) has been invoked for `Trait`. Trait objects can't be
+/// hashed, so each new value is compared with every interned value, and interning `n` values is
+/// quadratic in the worst case.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynEq, DynInterner};
+///
+/// trait Type: DynEq {}
+/// dyn_eq::eq_trait_object!(Type);
+///
+/// impl Type for u8 {}
+/// impl Type for u16 {}
+///
+/// let mut interner: DynInterner<dyn Type> = DynInterner::new();
+/// let a = interner.intern(Box::new(1u8));
+/// let b = interner.intern(Box::new(1u16));
+/// let c = interner.intern(Box::new(1u8));
+///
+/// assert_eq!(a, c);
+/// assert_ne!(a, b);
+/// assert!(interner.resolve(b) == &1u16 as &dyn Type);
+/// assert_eq!(interner.get(&1u16), Some(b));
+/// ```
+#[derive(Debug)]
+pub struct DynInterner<T: ?Sized> {
+	/// The distinct values, indexed by their identifier.
+	values: Vec<Box<T>>,
+}
+
+impl<T: ?Sized> DynInterner<T> {
+	/// Creates an empty interner.
+	pub const fn new() -> Self {
+		Self { values: Vec::new() }
+	}
+
+	/// Returns the number of distinct values.
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Returns `true` if no value was interned.
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	/// Returns the value with the identifier `id`.
+	///
+	/// # Panics
+	///
+	/// Panics if `id` wasn't returned by this interner.
+	pub fn resolve(&self, id: InternId) -> &T {
+		&self.values[id.0]
+	}
+
+	/// Iterates over the identifiers and the values, in the order in which they were first
+	/// interned.
+	pub fn iter(&self) -> impl Iterator<Item = (InternId, &T)> {
+		self.values
+			.iter()
+			.enumerate()
+			.map(|(i, value)| (InternId(i), &**value))
+	}
+}
+
+impl<T: ?Sized + PartialEq> DynInterner<T> {
+	/// Interns a value, and returns its identifier.
+	///
+	/// If an equal value was already interned, `value` is dropped and the identifier of the
+	/// existing value is returned.
+	///
+	/// This compares `value` with every interned value until an equal one is found, so it takes
+	/// `O(n)` comparisons for an interner of `n` values.
+	pub fn intern(&mut self, value: Box<T>) -> InternId {
+		self.get(&value).unwrap_or_else(|| {
+			self.values.push(value);
+			InternId(self.values.len() - 1)
+		})
+	}
+
+	/// Returns the identifier of the interned value equal to `value`, or `None` if there is none.
+	///
+	/// Like [`intern`](Self::intern), this takes `O(n)` comparisons.
+	pub fn get(&self, value: &T) -> Option<InternId> {
+		self.values
+			.iter()
+			.position(|other| **other == *value)
+			.map(InternId)
+	}
+}
+
+impl<T: ?Sized> Default for DynInterner<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Generated tests of the equivalence laws.
+
+/// Generates a module of tests checking that the comparisons of a trait object follow the laws
+/// of an equivalence relation, for a list of sample values.
+///
+/// The module is named by the first argument, and contains these tests:
+/// - `reflexivity`: every value is equal to itself.
+/// - `symmetry`: `a == b` if and only if `b == a`, and `a != b` is always `!(a == b)`.
+/// - `transitivity`: if `a == b` and `b == c`, then `a == c`.
+/// - `different_types`: values of different concrete types are never equal. This test fails for
+///   traits using the `cross_type(...)` option of [`eq_trait_object`](crate::eq_trait_object).
+///
+/// A broken [`Eq`] implementation of one of the types silently corrupts the comparisons of the
+/// trait objects, so the samples should include several values of each type, both equal and
+/// different. The trait and the values are resolved from inside the generated module, which
+/// imports everything from the module where the macro is invoked.
+///
+/// # Example
+///
+/// Documentation tests don't compile the `#[test]` functions, so this example doesn't run any
+/// check; the generated tests only run with `cargo test`, as in the `my_trait_laws` module of the
+/// integration tests of this crate.
+///
+/// ```no_run
+/// use dyn_eq::DynEq;
+///
+/// trait Shape: DynEq {}
+/// dyn_eq::eq_trait_object!(Shape);
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Circle(u32);
+/// #[derive(PartialEq, Eq)]
+/// struct Square(u32);
+///
+/// impl Shape for Circle {}
+/// impl Shape for Square {}
+///
+/// dyn_eq::dyn_eq_law_tests!(
+///     shape_laws,
+///     Shape,
+///     [Circle(1), Circle(1), Circle(2), Square(1)]
+/// );
+/// ```
+#[macro_export]
+macro_rules! dyn_eq_law_tests {
+	($name:ident, $trait:path, [$($value:expr),+ $(,)?]) => {
+		mod $name {
+			#[allow(unused_imports)]
+			use super::*;
+
+			#[test]
+			#[allow(clippy::eq_op)]
+			fn reflexivity() {
+				let values: &[&dyn $trait] = &[$(&$value),+];
+				for (i, a) in values.iter().enumerate() {
+					::core::assert!(*a == *a, "value {} isn't equal to itself", i);
+				}
+			}
+
+			#[test]
+			fn symmetry() {
+				let values: &[&dyn $trait] = &[$(&$value),+];
+				for (i, a) in values.iter().enumerate() {
+					for (j, b) in values.iter().enumerate() {
+						::core::assert_eq!(*a == *b, *b == *a, "values {} and {} aren't symmetric", i, j);
+						::core::assert_eq!(*a != *b, !(*a == *b), "`!=` and `==` disagree for values {} and {}", i, j);
+					}
+				}
+			}
+
+			#[test]
+			fn transitivity() {
+				let values: &[&dyn $trait] = &[$(&$value),+];
+				for (i, a) in values.iter().enumerate() {
+					for (j, b) in values.iter().enumerate() {
+						for (k, c) in values.iter().enumerate() {
+							if *a == *b && *b == *c {
+								::core::assert!(*a == *c, "values {} and {} are equal to value {}, but not to each other", i, k, j);
+							}
+						}
+					}
+				}
+			}
+
+			#[test]
+			fn different_types() {
+				let values: &[&dyn $trait] = &[$(&$value),+];
+				for (i, a) in values.iter().enumerate() {
+					for (j, b) in values.iter().enumerate() {
+						if <dyn $trait as $crate::DynEq>::dyn_type_id(*a) != <dyn $trait as $crate::DynEq>::dyn_type_id(*b) {
+							::core::assert!(*a != *b, "values {} and {} have different types, but are equal", i, j);
+						}
+					}
+				}
+			}
+		}
+	};
+}
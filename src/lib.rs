@@ -25,9 +25,39 @@
 //!
 //! # Features
 //!
-//! This crate has one feature: `alloc`, which is enabled by default. Disabling
-//! this feature removes the dependency on the [`alloc`] crate, but you won't be
-//! able to use [`DynEq`] for `Box<dyn Trait>`.
+//! - `alloc` (enabled by default): disabling this feature removes the dependency on the
+//!   [`alloc`] crate, but you won't be able to use [`DynEq`] for `Box<dyn Trait>`, nor any of the
+//!   features below.
+//! - `rc` (enabled by default): support for `Rc<dyn Trait>`, and `weak::rc_eq`.
+//! - `arc` (enabled by default): support for `Arc<dyn Trait>`, and `weak::arc_eq`.
+//! - `collections` (enabled by default): the `vec` and `batch` modules, `EqCache`, `DynCounter`,
+//!   `DynInterner` and `DynMap`.
+//! - `pretty-diff`: failures of [`assert_dyn_eq`] show a colored line by line difference
+//!   between the values, instead of the values themselves.
+//! - `strict-checks` (requires `std`): when debug assertions are enabled, every comparison of two
+//!   values of the same type checks that their [`Eq`] implementation is reflexive and symmetric,
+//!   and panics otherwise. This catches broken implementations in tests, at the cost of a few
+//!   additional comparisons. Only the outermost comparison of a thread is checked, not those of
+//!   the trait objects nested in the compared values, and values of zero-sized types are never
+//!   compared, so they aren't checked.
+//! - `core-error` (Rust 1.81+): adds `DynError`, a comparable [`Error`](core::error::Error).
+//! - `type-name`: adds `DynEq::dyn_type_name`, which embeds the name of every type
+//!   implementing [`DynEq`] in the binary.
+//! - `trait-upcasting` (Rust 1.86+): the implementations generated by [`eq_trait_object`]
+//!   upcast the trait objects to [`Any`] directly, instead of calling a method of [`DynEq`] to do
+//!   it, which saves a virtual call per comparison. This only changes the calls: the method is
+//!   still part of [`DynEq`], so the vtables don't get smaller, because the generic helpers of
+//!   this crate can't upcast a value whose type isn't known to be a trait object. Also adds
+//!   `upcast_eq_trait_object!`, which compares the trait objects of a trait and of one of its
+//!   supertraits.
+//! - `unstable-specialization` (nightly only): uses specialization so that [`eq`] compares
+//!   values directly with [`Eq`], without going through [`Any`], when both are statically known
+//!   to be of the same type. Comparisons between two
+//!   trait objects are unaffected, since their types are only known at runtime.
+//! - `allocator-api` (nightly only): the implementations generated by [`eq_trait_object`] cover
+//!   boxes using any [`Allocator`](core::alloc::Allocator), such as `Box<dyn Trait, &Bump>`,
+//!   instead of only the global allocator. Trait objects themselves are still only compared with
+//!   boxes using the global allocator.
 //!
 //! [`alloc`]: https://doc.rust-lang.org/alloc/
 //!
@@ -58,36 +88,201 @@
 //! // Even if the value is the same, the fact that it's a diffrent type means it's not equal
 //! assert!(a != c);
 //!
-//! // Now data structures containing Box<dyn MyTrait> can derive Eq (only when `alloc`
-//! // feature is enabled).
-//! # #[cfg(feature = "alloc")]
+//! // Now data structures containing Box<dyn MyTrait>, Rc<dyn MyTrait> or Arc<dyn MyTrait>
+//! // can derive Eq (only when `alloc`, `rc` and `arc` features are enabled).
+//! # #[cfg(all(feature = "rc", feature = "arc"))]
 //! #[derive(PartialEq, Eq)]
 //! struct Container {
-//!     field: Box<dyn MyTrait>
+//!     field: Box<dyn MyTrait>,
+//!     shared: std::rc::Rc<dyn MyTrait>,
+//!     thread_safe: std::sync::Arc<dyn MyTrait>,
 //! }
 //! ```
+//!
+//! # Other traits
+//!
+//! Unlike [`Eq`], most standard traits such as [`Debug`](core::fmt::Debug) can be used on trait
+//! objects directly, so they don't need a companion of [`DynEq`]: adding them as supertraits is
+//! enough for `dyn Trait`, and for data structures containing it, to implement them.
+//!
+//! ```
+//! use core::fmt::Debug;
+//!
+//! use dyn_eq::DynEq;
+//!
+//! trait MyTrait: DynEq + Debug {}
+//! dyn_eq::eq_trait_object!(MyTrait);
+//!
+//! impl MyTrait for u8 {}
+//!
+//! # #[cfg(feature = "alloc")]
+//! #[derive(Debug, PartialEq, Eq)]
+//! struct Container {
+//!     field: Box<dyn MyTrait>,
+//! }
+//!
+//! # #[cfg(feature = "alloc")]
+//! assert_eq!(
+//!     format!(
+//!         "{:?}",
+//!         Container {
+//!             field: Box::new(5u8)
+//!         }
+//!     ),
+//!     "Container { field: 5 }"
+//! );
+//! ```
 #![no_std]
+#![cfg_attr(feature = "unstable-specialization", allow(incomplete_features))]
+#![cfg_attr(feature = "unstable-specialization", feature(specialization))]
+// Lets the impls generated in other crates use `Allocator` without them enabling its feature.
+#![cfg_attr(feature = "allocator-api", allow(internal_features))]
+#![cfg_attr(
+	feature = "allocator-api",
+	feature(allocator_api, allow_internal_unstable)
+)]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "strict-checks")]
+extern crate std;
 
 /// Re-export of [`alloc::boxed::Box`] for the macro.
 #[cfg(feature = "alloc")]
 #[doc(hidden)]
 pub use alloc::boxed::Box;
-use core::any::Any;
+/// Re-export of [`alloc::rc::Rc`] for the macro.
+#[cfg(feature = "rc")]
+#[doc(hidden)]
+pub use alloc::rc::Rc;
+/// Re-export of [`alloc::sync::Arc`] for the macro.
+#[cfg(all(feature = "arc", target_has_atomic = "ptr"))]
+#[doc(hidden)]
+pub use alloc::sync::Arc;
+use core::any::{Any, TypeId};
 
+#[cfg(feature = "alloc")]
+pub use any_eq::AnyEq;
+#[cfg(feature = "collections")]
+pub use cache::EqCache;
+pub use changed::Changed;
+pub use cmp::{eq, eq_by_key, ne};
+#[cfg(feature = "collections")]
+pub use counter::DynCounter;
+pub use cross::CrossTypeEq;
+pub use diff::{DynComparison, DynDifference, compare, diff};
+#[cfg(feature = "alloc")]
+pub use downcast::downcast;
+pub use downcast::{downcast_mut, downcast_ref, is};
+#[cfg(feature = "core-error")]
+pub use error::DynError;
+pub use ext::DynEqExt;
+#[cfg(feature = "collections")]
+pub use interner::{DynInterner, InternId};
+pub use manual::{Manual, ManualEq};
+#[cfg(feature = "collections")]
+pub use map::DynMap;
+pub use total::Total;
+#[cfg(feature = "alloc")]
+pub use wrapper::{DynEqWrapper, WrapperMarkers};
+
+#[cfg(feature = "alloc")]
+mod any_eq;
+mod assert;
+#[cfg(feature = "collections")]
+pub mod batch;
+#[cfg(feature = "collections")]
+mod cache;
+mod changed;
+mod cmp;
+#[cfg(feature = "collections")]
+mod counter;
+mod cross;
+mod diff;
+mod downcast;
+#[cfg(feature = "core-error")]
+mod error;
+mod ext;
+#[cfg(feature = "collections")]
+mod interner;
+mod laws;
 mod macros;
+mod manual;
+#[cfg(feature = "collections")]
+pub mod map;
+pub mod matchers;
+pub mod prelude;
+pub mod slice;
+#[cfg(feature = "strict-checks")]
+mod strict;
+mod total;
+#[cfg(feature = "collections")]
+pub mod vec;
+#[cfg(any(feature = "rc", all(feature = "arc", target_has_atomic = "ptr")))]
+pub mod weak;
+#[cfg(feature = "alloc")]
+mod wrapper;
 
 /// This trait is implemented by any type that implements [`Eq`].
+///
+/// Only sized types can implement it: an unsized type such as `str` or `[u8]` can neither be
+/// turned into a trait object nor into a `&dyn Any`, so it couldn't be compared through a trait
+/// object anyway. Use a sized wrapper (e.g. `Box<str>` or `Vec<u8>`) instead.
+///
+/// Since the comparison relies on [`Any`] to check that both values have the same type, only
+/// `'static` types can implement it. Types borrowing data can't be compared safely this way: for
+/// them, the lifetimes of both values would have to be proven identical, which [`Any`] can't do.
+///
+/// Values of a zero-sized type are always equal to each other, so they're compared without
+/// calling [`Eq`].
+///
+/// `dyn DynEq` (and its `Send`/`Sync` variants) can itself be compared, which is convenient to
+/// store any comparable value without declaring a trait:
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// let a: &dyn DynEq = &5u8;
+/// assert!(a == &5u8 as &dyn DynEq);
+/// assert!(a != &5u16 as &dyn DynEq);
+///
+/// # #[cfg(feature = "alloc")]
+/// let values: Vec<Box<dyn DynEq>> = vec![Box::new(5u8), Box::new("five")];
+/// # #[cfg(feature = "alloc")]
+/// assert!(values.contains(&(Box::new("five") as Box<dyn DynEq>)));
+/// ```
+///
+/// Note that `Box<dyn Trait>` also implements this trait once [`eq_trait_object`] has been
+/// invoked, so calling a method on a box (`boxed.as_any_mut()`) will use the box itself and not
+/// the trait object inside. Use `(*boxed).as_any_mut()` to reach the trait object.
 pub trait DynEq: Any + private::Sealed {
 	/// Upcast this reference to a `&dyn Any`, which can then be passed to [`dyn_eq`](DynEq::dyn_eq).
 	#[doc(hidden)]
 	fn as_any(&self) -> &dyn Any;
 
+	/// Upcast this mutable reference to a `&mut dyn Any`, which can then be downcasted to the
+	/// concrete type.
+	fn as_any_mut(&mut self) -> &mut dyn Any;
+
+	/// Upcast this box to a `Box<dyn Any>`, which can then be downcasted to the concrete type.
+	#[cfg(feature = "alloc")]
+	fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
 	/// This method tests for self and other values to be equal.
 	#[doc(hidden)]
 	fn dyn_eq(&self, other: &dyn Any) -> bool;
+
+	/// This method tests for self and other values to be different, using the [`ne`](PartialEq::ne)
+	/// implementation of the concrete type.
+	#[doc(hidden)]
+	fn dyn_ne(&self, other: &dyn Any) -> bool;
+
+	/// Returns the [`TypeId`] of the concrete type.
+	fn dyn_type_id(&self) -> TypeId;
+
+	/// Returns the name of the concrete type, as given by [`type_name`](core::any::type_name).
+	#[cfg(feature = "type-name")]
+	fn dyn_type_name(&self) -> &'static str;
 }
 
 impl<T: Eq + 'static> DynEq for T {
@@ -95,11 +290,197 @@ impl<T: Eq + 'static> DynEq for T {
 		self
 	}
 
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		self
+	}
+
+	#[cfg(feature = "alloc")]
+	fn into_any(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+
+	#[inline]
 	fn dyn_eq(&self, other: &dyn Any) -> bool {
-		other.downcast_ref().map_or(false, |other| self == other)
+		// Values of different types are the most common case in heterogeneous collections, so
+		// the type ids are compared before anything else.
+		if other.type_id() != TypeId::of::<T>() {
+			return false;
+		}
+		// All the values of a zero-sized type are the same
+		if size_of::<T>() == 0 {
+			return true;
+		}
+		match other.downcast_ref::<T>() {
+			Some(other) => {
+				#[cfg(feature = "strict-checks")]
+				return strict::eq(self, other);
+				#[cfg(not(feature = "strict-checks"))]
+				return self == other;
+			}
+			None => false,
+		}
+	}
+
+	#[inline]
+	fn dyn_ne(&self, other: &dyn Any) -> bool {
+		if other.type_id() != TypeId::of::<T>() {
+			return true;
+		}
+		if size_of::<T>() == 0 {
+			return false;
+		}
+		match other.downcast_ref::<T>() {
+			Some(other) => {
+				#[cfg(feature = "strict-checks")]
+				return strict::ne(self, other);
+				#[cfg(not(feature = "strict-checks"))]
+				return self != other;
+			}
+			None => true,
+		}
+	}
+
+	fn dyn_type_id(&self) -> TypeId {
+		TypeId::of::<T>()
+	}
+
+	#[cfg(feature = "type-name")]
+	fn dyn_type_name(&self) -> &'static str {
+		core::any::type_name::<T>()
 	}
 }
 
+/// Items used by the macros.
+#[doc(hidden)]
+pub mod __private {
+	#[cfg(feature = "allocator-api")]
+	pub use core::alloc::Allocator;
+	#[cfg(feature = "trait-upcasting")]
+	use core::any::Any;
+
+	pub use crate::assert::{
+		DebugFallback, DebugSpecialized, DebugWrap, assert_failed, type_description,
+	};
+	use crate::{CrossTypeEq, DynEq};
+
+	/// Same as [`DynEq`], but with an error message telling what's wrong when it's not
+	/// implemented by a trait object.
+	#[diagnostic::on_unimplemented(
+		message = "`{Self}` must have `DynEq` as a supertrait to use `eq_trait_object!`",
+		label = "the trait of this object doesn't have `DynEq` as a supertrait"
+	)]
+	pub trait RequireDynEq: DynEq {}
+	impl<T: ?Sized + DynEq> RequireDynEq for T {}
+
+	/// Implemented by every marker combination of a trait object (including none), so a single
+	/// generic [`PartialEq`] implementation can compare a trait object with all of them.
+	pub trait SameTrait<T: ?Sized> {
+		/// Returns the trait object without its marker traits.
+		fn as_plain(&self) -> &T;
+	}
+
+	/// Implemented by the trait objects given to [`eq_trait_object`](crate::eq_trait_object),
+	/// and only by them, so that [`DynEqExt`](crate::DynEqExt) isn't implemented by the pointers
+	/// containing them.
+	pub trait TraitObject {}
+
+	/// Compare two trait objects for equality, or for inequality if `ne` is `true`.
+	///
+	/// Both are done by the same function, so the generated impls call it from a single place and
+	/// a trait without `DynEq` as a supertrait only gets one error.
+	#[cfg(not(feature = "trait-upcasting"))]
+	#[inline]
+	pub fn dyn_cmp<T: ?Sized + RequireDynEq>(this: &T, other: &T, ne: bool) -> bool {
+		if ne {
+			this.dyn_ne(other.as_any())
+		} else {
+			this.dyn_eq(other.as_any())
+		}
+	}
+
+	/// Compare two trait objects for equality, or for inequality if `ne` is `true`, `other` being
+	/// upcasted by the caller.
+	#[cfg(feature = "trait-upcasting")]
+	#[inline]
+	pub fn dyn_cmp<T: ?Sized + RequireDynEq>(this: &T, other: &dyn Any, ne: bool) -> bool {
+		if ne {
+			this.dyn_ne(other)
+		} else {
+			this.dyn_eq(other)
+		}
+	}
+
+	/// Compare two trait objects that may be of types `A` and `B`.
+	#[inline]
+	pub fn cross_type_eq<A, B, T>(this: &T, other: &T) -> bool
+	where
+		A: CrossTypeEq<B> + 'static,
+		B: 'static,
+		T: ?Sized + RequireDynEq,
+	{
+		let (this, other) = (this.as_any(), other.as_any());
+		match (this.downcast_ref::<A>(), other.downcast_ref::<B>()) {
+			(Some(a), Some(b)) => a.cross_eq(b),
+			_ => match (other.downcast_ref::<A>(), this.downcast_ref::<B>()) {
+				(Some(a), Some(b)) => a.cross_eq(b),
+				_ => false,
+			},
+		}
+	}
+
+	/// Compare two trait objects of different traits.
+	#[inline]
+	pub fn cross_dyn_eq<T, U>(this: &T, other: &U) -> bool
+	where
+		T: ?Sized + RequireDynEq,
+		U: ?Sized + RequireDynEq,
+	{
+		this.dyn_eq(other.as_any())
+	}
+
+	/// Compare two trait objects of different traits for inequality.
+	#[inline]
+	pub fn cross_dyn_ne<T, U>(this: &T, other: &U) -> bool
+	where
+		T: ?Sized + RequireDynEq,
+		U: ?Sized + RequireDynEq,
+	{
+		this.dyn_ne(other.as_any())
+	}
+
+	/// Compare two trait objects for equality, or for inequality if `ne` is `true`, starting with
+	/// their addresses.
+	#[cfg(not(feature = "trait-upcasting"))]
+	#[inline]
+	pub fn ptr_dyn_cmp<T: ?Sized + RequireDynEq>(this: &T, other: &T, ne: bool) -> bool {
+		let same = core::ptr::addr_eq(this, other) && this.dyn_type_id() == other.dyn_type_id();
+		if ne {
+			!same && this.dyn_ne(other.as_any())
+		} else {
+			same || this.dyn_eq(other.as_any())
+		}
+	}
+
+	/// Compare two trait objects for equality, or for inequality if `ne` is `true`, starting with
+	/// their addresses, `other` being upcasted by the caller.
+	#[cfg(feature = "trait-upcasting")]
+	#[inline]
+	pub fn ptr_dyn_cmp<T: ?Sized + RequireDynEq>(this: &T, other: &dyn Any, ne: bool) -> bool {
+		let same = core::ptr::addr_eq(this, other) && this.dyn_type_id() == other.type_id();
+		if ne {
+			!same && this.dyn_ne(other)
+		} else {
+			same || this.dyn_eq(other)
+		}
+	}
+}
+
+// `dyn DynEq` can be used directly as an "any comparable value".
+#[allow(unused_parens)]
+const _: () = {
+	eq_trait_object!(DynEq);
+};
+
 /// Private module to seal the [`DynEq`] trait.
 mod private {
 	/// Sealing trait.
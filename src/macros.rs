@@ -11,9 +11,31 @@
 
 /// Implement [`PartialEq`] and [`Eq`] for a trait object that has [`DynEq`] as a supertrait.
 ///
+/// When the `alloc`, `rc` and `arc` features are enabled, this also makes respectively
+/// `Box<dyn Trait>`, `Rc<dyn Trait>` and `Arc<dyn Trait>` usable in structs deriving
+/// [`PartialEq`] and [`Eq`] (see [rust#31740]).
+/// `Box<dyn Trait>`, `Rc<dyn Trait>` and `Arc<dyn Trait>` can also be compared directly with a
+/// `dyn Trait`, and a `dyn Trait` can be compared with a `Box<dyn Trait>`.
+///
+/// Trait objects with different marker traits (for example `dyn Trait` and
+/// `dyn Trait + Send + Sync`) can also be compared with each other, and so can their boxed
+/// forms.
+///
+/// Both [`eq`](PartialEq::eq) and [`ne`](PartialEq::ne) are forwarded to the concrete type, so a
+/// custom `ne` implementation is used when comparing trait objects with `!=`.
+///
+/// Pinned pointers such as `Pin<Box<dyn Trait>>` don't need any additional implementation: the
+/// [`PartialEq`] implementation of [`Pin`](core::pin::Pin) is generic over the compared pointer,
+/// so they can be compared and used in derives as soon as this macro has been invoked.
+///
+/// For the same reason, newtypes such as `struct Handle(Box<dyn Trait>)` only need to derive
+/// [`PartialEq`] and [`Eq`], there's nothing to forward manually.
+///
 /// # Examples
 ///
 /// See the [crate's documentation](https://docs.rs/dyn-eq/latest/dyn_eq/#example) for a basic example. \
+/// The trait can be named by any path (e.g. `crate::model::Node`, `super::Node` or
+/// `::my_crate::Node`), so the macro can be invoked away from the trait's definition. \
 /// The macro also supports traits that have type parameters and/or where clauses.
 ///
 /// ```
@@ -27,11 +49,323 @@
 /// dyn_eq::eq_trait_object!(<R> Difficult<R> where R: Read + 'static);
 /// ```
 ///
+/// Associated types can be bound in the trait path, like they would be in the trait object type.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Producer: DynEq {
+///     type Item;
+/// }
+///
+/// dyn_eq::eq_trait_object!(<T> Producer<Item = T> where T: 'static);
+/// ```
+///
+/// Lifetime parameters are supported as well, but since [`DynEq`] requires `'static`, they must
+/// be bound to `'static` in the where clause.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait View<'ctx>: DynEq {
+///     fn label(&self) -> &'ctx str;
+/// }
+///
+/// dyn_eq::eq_trait_object!(<'ctx> View<'ctx> where 'ctx: 'static);
+/// ```
+///
+/// Bounds in the generics list can have generics themselves.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Codec<T: Into<Vec<u8>>>: DynEq {}
+///
+/// dyn_eq::eq_trait_object!(<T: Into<Vec<u8>> + 'static> Codec<T>);
+/// ```
+///
+/// Bounds can be higher-ranked, both in the generics list and in the where clause.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Handler<F>: DynEq where F: for<'a> Fn(&'a str) -> bool {}
+///
+/// dyn_eq::eq_trait_object!(<F> Handler<F> where F: for<'a> Fn(&'a str) -> bool + 'static);
+/// ```
+///
+/// Const generic parameters are supported too. Default values of generic parameters can be kept,
+/// they will be removed from the implementations.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Fixed<const N: usize = 3>: DynEq {}
+/// trait Store<K, V = Vec<K>>: DynEq {}
+///
+/// dyn_eq::eq_trait_object!(<const N: usize = 3> Fixed<N>);
+/// dyn_eq::eq_trait_object!(<K: 'static, V: 'static = Vec<K>> Store<K, V>);
+/// ```
+///
+/// Several traits can be given at once, separated by commas. Since the `where` clause goes until
+/// the end of the list, only the last trait can have one.
+///
+/// ```
+/// use dyn_eq::DynEq;
+/// use std::io::Read;
+///
+/// trait First: DynEq {}
+/// trait Second<T>: DynEq {}
+/// trait Third<R>: DynEq where R: Read {}
+///
+/// dyn_eq::eq_trait_object!(First, <T: 'static> Second<T>, <R> Third<R> where R: Read + 'static);
+/// ```
+///
+/// Attributes placed before a trait, such as `#[cfg(...)]`, `#[allow(...)]` or `#[doc(hidden)]`,
+/// are forwarded to every generated implementation.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// #[cfg(feature = "plugins")]
+/// trait Plugin: DynEq {}
+///
+/// dyn_eq::eq_trait_object!(#[cfg(feature = "plugins")] Plugin);
+/// ```
+///
+/// Unsafe traits can be written with their `unsafe` keyword, to mirror their declaration. It is
+/// ignored, since the generated implementations don't implement the trait itself.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// unsafe trait Buffer: DynEq {}
+/// unsafe trait Channel<T>: DynEq {}
+///
+/// dyn_eq::eq_trait_object!(unsafe Buffer, <T: 'static> unsafe Channel<T>);
+/// ```
+///
+/// # Errors
+///
+/// The trait must have [`DynEq`] as a supertrait, otherwise the macro fails with an error saying
+/// so. It can also be an indirect supertrait, such as in `trait Node: Base {}` with
+/// `trait Base: DynEq {}`.
+///
+/// ```compile_fail,E0277
+/// trait MyTrait {}
+/// dyn_eq::eq_trait_object!(MyTrait);
+/// ```
+///
+/// Since [`DynEq`] requires `'static`, the type parameters of the trait must be bound to
+/// `'static`, otherwise the error suggests adding the bound.
+///
+/// ```compile_fail,E0310
+/// use dyn_eq::DynEq;
+/// use std::io::Read;
+///
+/// trait Difficult<R>: DynEq where R: Read {}
+/// dyn_eq::eq_trait_object!(<R> Difficult<R> where R: Read);
+/// ```
+///
+/// The markers must be auto traits, since they are added to the trait object types.
+///
+/// ```compile_fail,E0225
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+/// dyn_eq::eq_trait_object!(MyTrait; markers(Clone));
+/// ```
+///
+/// # Options
+///
+/// Options can be given after a `;`, separated by commas. They apply to every trait of the list.
+///
+/// - `markers(...)`: the marker trait combinations for which the implementations are generated,
+///   in addition to the plain `dyn Trait`. Defaults to `markers(Send, Sync, Send + Sync)`. Any
+///   auto trait can be used, which is needed if you use trait objects such as
+///   `dyn Trait + Send + UnwindSafe`.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+///
+/// // Only `dyn MyTrait` and `dyn MyTrait + Send` get the implementations...
+/// dyn_eq::eq_trait_object!(MyTrait; markers(Send));
+///
+/// // ...so they can still be written manually for the other combinations.
+/// impl PartialEq for dyn MyTrait + Sync {
+///     fn eq(&self, _: &Self) -> bool {
+///         false
+///     }
+/// }
+/// ```
+///
+/// ```
+/// use dyn_eq::DynEq;
+/// use std::panic::{RefUnwindSafe, UnwindSafe};
+///
+/// trait Task: DynEq {}
+/// dyn_eq::eq_trait_object!(Task; markers(
+///     Send,
+///     Sync,
+///     Send + Sync,
+///     Send + UnwindSafe,
+///     Send + Sync + UnwindSafe + RefUnwindSafe,
+/// ));
+///
+/// impl Task for u8 {}
+///
+/// let a: Box<dyn Task + Send + UnwindSafe> = Box::new(1u8);
+/// let b: Box<dyn Task + Send + UnwindSafe> = Box::new(1u8);
+/// assert!(a == b);
+/// ```
+///
+/// - `ptr_eq`: when both sides are the same value (same address and same type), consider them
+///   equal without calling [`Eq`]. This speeds up comparisons of shared values, such as
+///   `&'static dyn Trait` constants or cached boxes.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Interned: DynEq {}
+/// dyn_eq::eq_trait_object!(Interned; ptr_eq, markers(Send + Sync));
+///
+/// impl Interned for String {}
+///
+/// static EMPTY: &(dyn Interned + Send + Sync) = &String::new();
+/// assert!(EMPTY == EMPTY);
+/// ```
+///
+/// - `cross_type(A = B, ...)`: values of type `A` and `B` are compared with [`CrossTypeEq`]
+///   instead of always being different. `A` must implement `CrossTypeEq<B>`, and the comparison
+///   is done in both directions.
+///
+/// ```
+/// use dyn_eq::{CrossTypeEq, DynEq};
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Inches(u32);
+/// #[derive(PartialEq, Eq)]
+/// struct Tenths(u32);
+///
+/// impl CrossTypeEq<Tenths> for Inches {
+///     fn cross_eq(&self, other: &Tenths) -> bool {
+///         self.0 * 10 == other.0
+///     }
+/// }
+///
+/// trait Length: DynEq {}
+/// dyn_eq::eq_trait_object!(Length; cross_type(Inches = Tenths));
+///
+/// impl Length for Inches {}
+/// impl Length for Tenths {}
+///
+/// let a: &dyn Length = &Inches(2);
+/// let b: &dyn Length = &Tenths(20);
+/// assert!(a == b);
+/// assert!(b == a);
+/// ```
+///
+/// - `pointers(...)`: pointer types to trait objects that, like `Rc` and `Arc`, can be compared
+///   with the trait object and used in structs deriving [`PartialEq`] and [`Eq`]. Each pointer
+///   must implement [`Deref`](core::ops::Deref) and take the trait object as its only type
+///   parameter, use a type alias for pointers with more parameters. This doesn't need the `alloc`
+///   feature, so it can be used with inline containers such as `smallbox::SmallBox` on targets
+///   without an allocator.
+///
+/// ```
+/// use dyn_eq::DynEq;
+/// use std::ops::Deref;
+///
+/// // A pointer to a trait object stored somewhere else, such as an arena or a static
+/// pub struct Slot<T: ?Sized + 'static>(&'static T);
+///
+/// impl<T: ?Sized> Deref for Slot<T> {
+///     type Target = T;
+///
+///     fn deref(&self) -> &T {
+///         self.0
+///     }
+/// }
+///
+/// impl<T: ?Sized + PartialEq> PartialEq for Slot<T> {
+///     fn eq(&self, other: &Self) -> bool {
+///         **self == **other
+///     }
+/// }
+///
+/// trait Sensor: DynEq {}
+/// dyn_eq::eq_trait_object!(Sensor; pointers(Slot));
+///
+/// impl Sensor for u8 {}
+///
+/// #[derive(PartialEq)]
+/// struct Device {
+///     sensor: Slot<dyn Sensor>,
+/// }
+///
+/// let a = Device { sensor: Slot(&1u8) };
+/// let b = Device { sensor: Slot(&1u8) };
+/// assert!(a == b);
+/// assert!(a.sensor == *b.sensor);
+/// ```
+///
+/// - `static_only`: implement the comparisons only for `dyn Trait + 'static` instead of for any
+///   lifetime of the trait object. Since [`DynEq`] requires `'static`, this doesn't change which
+///   values can be compared, but the generated impls are simpler and don't introduce a generic
+///   lifetime that may confuse type inference next to other generic impls.
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Setting: DynEq {}
+/// dyn_eq::eq_trait_object!(Setting; static_only);
+///
+/// impl Setting for u8 {}
+///
+/// let a: Box<dyn Setting> = Box::new(1u8);
+/// let b: Box<dyn Setting> = Box::new(1u8);
+/// assert!(a == b);
+/// ```
+///
+/// - `mixed_pointers`: also compare `Box`, `Rc`, `Arc` and references to the trait object with
+///   each other, without dereferencing them first. `Rc` and `Arc` can't be compared with each
+///   other, since both are foreign types. These impls are opt-in since they add ten impls per
+///   marker combination.
+///
+/// ```
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// use dyn_eq::DynEq;
+///
+/// trait Resource: DynEq {}
+/// dyn_eq::eq_trait_object!(Resource; mixed_pointers);
+///
+/// impl Resource for u8 {}
+///
+/// # #[cfg(all(feature = "rc", feature = "arc"))]
+/// # {
+/// let owned: Box<dyn Resource> = Box::new(1u8);
+/// let shared: Rc<dyn Resource> = Rc::new(1u8);
+/// let thread_safe: Arc<dyn Resource> = Arc::new(1u8);
+/// let borrowed: &dyn Resource = &1u8;
+///
+/// assert!(owned == shared);
+/// assert!(thread_safe == owned);
+/// assert!(shared == borrowed);
+/// assert!(borrowed == owned);
+/// # }
+/// ```
+///
 /// [`DynEq`]: super::DynEq
+/// [`CrossTypeEq`]: super::CrossTypeEq
+/// [rust#31740]: https://github.com/rust-lang/rust/issues/31740
 #[macro_export]
 macro_rules! eq_trait_object {
-	($($path:tt)+) => {
-		$crate::__internal_eq_trait_object!(begin $($path)+);
+	($($input:tt)+) => {
+		$crate::__internal_eq_trait_object!(split [] $($input)+);
 	};
 }
 
@@ -39,80 +373,773 @@ macro_rules! eq_trait_object {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __internal_eq_trait_object {
-	// Invocation started with `<`, parse generics.
-	(begin < $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(generics () () $($rest)*);
+	// Found the options.
+	(split [$($traits:tt)*] ; $($options:tt)*) => {
+		$crate::__internal_eq_trait_object!(options [$($traits)*] {
+			markers [
+				()
+				(+ ::core::marker::Send)
+				(+ ::core::marker::Sync)
+				(+ ::core::marker::Send + ::core::marker::Sync)
+			]
+			eq dyn_cmp
+			cross []
+			pointers []
+			lifetime 'eq
+			mixed []
+		} $($options)*);
+	};
+
+	// No options.
+	(split [$($traits:tt)*]) => {
+		$crate::__internal_eq_trait_object!(split [$($traits)*] ;);
+	};
+
+	// Token before the options.
+	(split [$($traits:tt)*] $first:tt $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(split [$($traits)* $first] $($rest)*);
+	};
+
+	// End of options.
+	(options [$($traits:tt)*] $config:tt) => {
+		$crate::__internal_eq_trait_object!(begin $config () $($traits)*);
+	};
+
+	// Comma between options.
+	(options $traits:tt $config:tt , $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits $config $($rest)*);
+	};
+
+	// `markers(...)` option, parse the marker combinations.
+	(options $traits:tt $config:tt markers ($($markers:tt)*) $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(markers_list $traits $config [()] () ($($markers)*) ($($rest)*));
+	};
+
+	// `ptr_eq` option, compare the addresses before the values.
+	(options $traits:tt {markers $markers:tt eq $eq:tt cross $cross:tt pointers $pointers:tt lifetime $lifetime:tt mixed $mixed:tt} ptr_eq $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers $markers eq ptr_dyn_cmp cross $cross pointers $pointers lifetime $lifetime mixed $mixed} $($rest)*);
+	};
+
+	// `cross_type(...)` option, add pairs of types that can be equal.
+	(options $traits:tt {markers $markers:tt eq $eq:tt cross [$($cross:tt)*] pointers $pointers:tt lifetime $lifetime:tt mixed $mixed:tt} cross_type($($left:ty = $right:ty),* $(,)?) $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers $markers eq $eq cross [$($cross)* $({$left, $right})*] pointers $pointers lifetime $lifetime mixed $mixed} $($rest)*);
+	};
+
+	// `pointers(...)` option, add pointer types that can be compared with the trait object.
+	(options $traits:tt {markers $markers:tt eq $eq:tt cross $cross:tt pointers [$($pointers:tt)*] lifetime $lifetime:tt mixed $mixed:tt} pointers($($($pointer:ident)::+),* $(,)?) $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers $markers eq $eq cross $cross pointers [$($pointers)* $(($($pointer)::+))*] lifetime $lifetime mixed $mixed} $($rest)*);
+	};
+
+	// `static_only` option, only implement for `'static` trait objects.
+	(options $traits:tt {markers $markers:tt eq $eq:tt cross $cross:tt pointers $pointers:tt lifetime $lifetime:tt mixed $mixed:tt} static_only $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers $markers eq $eq cross $cross pointers $pointers lifetime 'static mixed $mixed} $($rest)*);
+	};
+
+	// `mixed_pointers` option, compare the different kinds of pointers with each other.
+	(options $traits:tt {markers $markers:tt eq $eq:tt cross $cross:tt pointers $pointers:tt lifetime $lifetime:tt mixed $mixed:tt} mixed_pointers $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers $markers eq $eq cross $cross pointers $pointers lifetime $lifetime mixed [mixed]} $($rest)*);
+	};
+
+	// End of marker combinations.
+	(markers_list $traits:tt {markers $old:tt $($config:tt)*} [$($markers:tt)*] () () ($($rest:tt)*)) => {
+		$crate::__internal_eq_trait_object!(options $traits {markers [$($markers)*] $($config)*} $($rest)*);
+	};
+
+	// End of the last marker combination.
+	(markers_list $traits:tt $config:tt [$($markers:tt)*] ($($current:tt)+) () $rest:tt) => {
+		$crate::__internal_eq_trait_object!(markers_list $traits $config [$($markers)* (+ $($current)+)] () () $rest);
+	};
+
+	// End of a marker combination.
+	(markers_list $traits:tt $config:tt [$($markers:tt)*] ($($current:tt)+) (, $($tokens:tt)*) $rest:tt) => {
+		$crate::__internal_eq_trait_object!(markers_list $traits $config [$($markers)* (+ $($current)+)] () ($($tokens)*) $rest);
+	};
+
+	// Token inside of a marker combination.
+	(markers_list $traits:tt $config:tt $markers:tt ($($current:tt)*) ($first:tt $($tokens:tt)*) $rest:tt) => {
+		$crate::__internal_eq_trait_object!(markers_list $traits $config $markers ($($current)* $first) ($($tokens)*) $rest);
+	};
+
+	// No more traits.
+	(begin $config:tt $attrs:tt) => {};
+
+	// Attribute forwarded to the impls.
+	(begin $config:tt ($($attrs:tt)*) #[$($attr:tt)*] $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(begin $config ($($attrs)* #[$($attr)*]) $($rest)*);
+	};
+
+	// Unsafe trait, the impls are the same as for other traits.
+	(begin $config:tt $attrs:tt unsafe $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(begin $config $attrs $($rest)*);
+	};
+
+	// Trait started with `<`, parse generics.
+	(begin $config:tt $attrs:tt < $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs () () $($rest)*);
 	};
 
-	// Invocation did not start with `<`.
-	(begin $first:tt $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(path () ($first) $($rest)*);
+	// Trait did not start with `<`.
+	(begin $config:tt $attrs:tt $first:tt $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs () ($first) () $($rest)*);
 	};
 
-	// End of generics.
-	(generics ($($generics:tt)*) () > $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(path ($($generics)*) () $($rest)*);
+	// Empty generics.
+	(generics $config:tt $attrs:tt () () > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs () () () $($rest)*);
+	};
+
+	// End of generics with a trailing comma.
+	(generics $config:tt $attrs:tt ($($generics:tt)*) () , > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs ($($generics)* ,) () () $($rest)*);
+	};
+
+	// End of generics. A trailing comma is added so other parameters can follow them in the impls.
+	(generics $config:tt $attrs:tt ($($generics:tt)*) () > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs ($($generics)* ,) () () $($rest)*);
+	};
+
+	// Default value of a generic parameter, which isn't allowed in impls.
+	(generics $config:tt $attrs:tt $generics:tt () = $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(default $config $attrs $generics () $($rest)*);
 	};
 
 	// Generics open bracket.
-	(generics ($($generics:tt)*) ($($brackets:tt)*) < $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(generics ($($generics)* <) ($($brackets)* <) $($rest)*);
+	(generics $config:tt $attrs:tt ($($generics:tt)*) ($($brackets:tt)*) < $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs ($($generics)* <) ($($brackets)* <) $($rest)*);
 	};
 
 	// Generics close bracket.
-	(generics ($($generics:tt)*) (< $($brackets:tt)*) > $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(generics ($($generics)* >) ($($brackets)*) $($rest)*);
+	(generics $config:tt $attrs:tt ($($generics:tt)*) (< $($brackets:tt)*) > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs ($($generics)* >) ($($brackets)*) $($rest)*);
+	};
+
+	// Generics close two brackets at once, like in `T: Into<Vec<u8>>`, or close a bracket and
+	// the generics, like in `<T: Into<u8>>`.
+	(generics $config:tt $attrs:tt $generics:tt (< $($brackets:tt)*) >> $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs $generics (< $($brackets)*) > > $($rest)*);
 	};
 
 	// Token inside of generics.
-	(generics ($($generics:tt)*) ($($brackets:tt)*) $first:tt $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(generics ($($generics)* $first) ($($brackets)*) $($rest)*);
+	(generics $config:tt $attrs:tt ($($generics:tt)*) ($($brackets:tt)*) $first:tt $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs ($($generics)* $first) ($($brackets)*) $($rest)*);
+	};
+
+	// End of default value and of generics, with a trailing comma.
+	(default $config:tt $attrs:tt ($($generics:tt)*) () , > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs ($($generics)* ,) () () $($rest)*);
+	};
+
+	// End of default value, followed by other generics.
+	(default $config:tt $attrs:tt ($($generics:tt)*) () , $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(generics $config $attrs ($($generics)* ,) () $($rest)*);
+	};
+
+	// End of default value and of generics.
+	(default $config:tt $attrs:tt ($($generics:tt)*) () > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs ($($generics)* ,) () () $($rest)*);
+	};
+
+	// Default value open bracket.
+	(default $config:tt $attrs:tt $generics:tt ($($brackets:tt)*) < $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(default $config $attrs $generics ($($brackets)* <) $($rest)*);
 	};
 
-	// End with `where` clause.
-	(path ($($generics:tt)*) ($($path:tt)*) where $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(impl ($($generics)*) ($($path)*) ($($rest)*));
+	// Default value close bracket.
+	(default $config:tt $attrs:tt $generics:tt (< $($brackets:tt)*) > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(default $config $attrs $generics ($($brackets)*) $($rest)*);
+	};
+
+	// Default value closes two brackets at once, like in `= Vec<Vec<u8>>`, or closes a bracket
+	// and the generics, like in `= Vec<u8>>`.
+	(default $config:tt $attrs:tt $generics:tt (< $($brackets:tt)*) >> $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(default $config $attrs $generics ($($brackets)*) > $($rest)*);
+	};
+
+	// Token inside of default value.
+	(default $config:tt $attrs:tt $generics:tt $brackets:tt $first:tt $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(default $config $attrs $generics $brackets $($rest)*);
+	};
+
+	// Unsafe trait after generics, the impls are the same as for other traits.
+	(path $config:tt $attrs:tt $generics:tt () () unsafe $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs $generics () () $($rest)*);
+	};
+
+	// End with `where` clause, which goes until the end of the invocation.
+	(path $config:tt $attrs:tt $generics:tt $path:tt () where $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(impl $config $attrs $generics $path ($($rest)*));
+	};
+
+	// End without `where` clause, followed by other traits.
+	(path $config:tt $attrs:tt $generics:tt $path:tt () , $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(impl $config $attrs $generics $path ());
+		$crate::__internal_eq_trait_object!(begin $config () $($rest)*);
 	};
 
 	// End without `where` clause.
-	(path ($($generics:tt)*) ($($path:tt)*)) => {
-		$crate::__internal_eq_trait_object!(impl ($($generics)*) ($($path)*) ());
+	(path $config:tt $attrs:tt $generics:tt $path:tt ()) => {
+		$crate::__internal_eq_trait_object!(impl $config $attrs $generics $path ());
+	};
+
+	// Path open bracket.
+	(path $config:tt $attrs:tt $generics:tt ($($path:tt)*) ($($brackets:tt)*) < $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs $generics ($($path)* <) ($($brackets)* <) $($rest)*);
+	};
+
+	// Path close bracket.
+	(path $config:tt $attrs:tt $generics:tt ($($path:tt)*) (< $($brackets:tt)*) > $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs $generics ($($path)* >) ($($brackets)*) $($rest)*);
+	};
+
+	// Path closes two brackets at once, like in `Codec<Vec<u8>>`.
+	(path $config:tt $attrs:tt $generics:tt $path:tt (< $($brackets:tt)*) >> $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs $generics $path (< $($brackets)*) > > $($rest)*);
 	};
 
 	// Token inside of path.
-	(path ($($generics:tt)*) ($($path:tt)*) $first:tt $($rest:tt)*) => {
-		$crate::__internal_eq_trait_object!(path ($($generics)*) ($($path)* $first) $($rest)*);
+	(path $config:tt $attrs:tt $generics:tt ($($path:tt)*) $brackets:tt $first:tt $($rest:tt)*) => {
+		$crate::__internal_eq_trait_object!(path $config $attrs $generics ($($path)* $first) $brackets $($rest)*);
 	};
 
-	// The impl.
-	(impl ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq for (dyn $($path)* + 'eq) where $($bound)* {
-			fn eq(&self, other: &Self) -> bool {
-				self.dyn_eq(DynEq::as_any(other))
+	// The impls. The lifetime of the trait objects is either a generic parameter or `'static`, it
+	// is added to the generics and the path so the other rules don't have to care about it.
+	(impl {markers $markers:tt eq $eq:tt cross $cross:tt pointers $pointers:tt lifetime 'static mixed $mixed:tt} $attrs:tt $generics:tt ($($path:tt)*) $bound:tt) => {
+		$crate::__internal_eq_trait_object!(impls {markers $markers eq $eq cross $cross pointers $pointers mixed $mixed} $attrs $generics ($($path)* + 'static) $bound);
+	};
+	(impl {markers $markers:tt eq $eq:tt cross $cross:tt pointers $pointers:tt lifetime $lifetime:lifetime mixed $mixed:tt} $attrs:tt ($($generics:tt)*) ($($path:tt)*) $bound:tt) => {
+		$crate::__internal_eq_trait_object!(impls {markers $markers eq $eq cross $cross pointers $pointers mixed $mixed} $attrs ($lifetime, $($generics)*) ($($path)* + $lifetime) $bound);
+	};
+	(impls {markers $markers:tt eq $eq:tt cross $cross:tt pointers [$($pointer:tt)*] mixed [$($mixed:ident)?]} $attrs:tt $generics:tt $path:tt $bound:tt) => {
+		$crate::__internal_eq_trait_object!(markers $attrs $generics $path $bound $eq $cross $markers);
+		$($crate::__internal_eq_trait_object!(pointer $attrs $generics $path $bound $pointer $markers);)*
+		$($crate::__internal_eq_trait_object_alloc!($mixed $attrs $generics $path $bound $markers);)?
+	};
+
+	// Implement everything for the given marker combinations, the first one is always the plain
+	// trait object.
+	// Each marker combination is compared with all the others by a single impl, generic over the
+	// right-hand side, so the number of impls only grows linearly with the number of combinations.
+	(markers $attrs:tt $generics:tt $path:tt $bound:tt $eq:tt $cross:tt [() $($markers:tt)*]) => {
+		$crate::__internal_eq_trait_object!(plain $attrs $generics $path $bound $eq $cross);
+		$($crate::__internal_eq_trait_object!(single $attrs $generics $path $bound $markers);)*
+		$crate::__internal_eq_trait_object!(cross $attrs $generics $path $bound [() $($markers)*]);
+	};
+
+	// The impl for the plain trait object. This is the only one that really uses `DynEq`, and it
+	// only does so in one place, so if the trait doesn't have it as a supertrait there's only one
+	// error.
+	(plain ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) $cmp:ident [$({$left:ty, $right:ty})*]) => {
+		$($attrs)*
+		const _: () = {
+			#[inline]
+			#[allow(clippy::multiple_bound_locations)]
+			fn dyn_eq_compare<$($generics)*>(this: &(dyn $($path)*), other: &(dyn $($path)*), ne: bool) -> bool where $($bound)* {
+				let unequal = $crate::__private::$cmp(this, other, ne) == ne;
+				// `cross_type(...)` pairs can only make unequal values equal
+				let equal = !unequal $(|| $crate::__private::cross_type_eq::<$left, $right, _>(this, other))*;
+				equal != ne
+			}
+
+			impl<$($generics)* DynEqRhs> ::core::cmp::PartialEq<DynEqRhs> for (dyn $($path)*)
+			where
+				DynEqRhs: ?::core::marker::Sized + $crate::__private::SameTrait<dyn $($path)*>,
+				$($bound)*
+			{
+				fn eq(&self, other: &DynEqRhs) -> bool {
+					dyn_eq_compare(self, other.as_plain(), false)
+				}
+
+				#[allow(clippy::partialeq_ne_impl)]
+				fn ne(&self, other: &DynEqRhs) -> bool {
+					dyn_eq_compare(self, other.as_plain(), true)
+				}
+			}
+		};
+
+		$crate::__internal_eq_trait_object!(common ($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ());
+	};
+
+	// The impl for a single marker combination, which delegates to the plain trait object.
+	(single ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)* DynEqRhs> ::core::cmp::PartialEq<DynEqRhs> for (dyn $($path)* $($marker)*)
+		where
+			DynEqRhs: ?::core::marker::Sized + $crate::__private::SameTrait<dyn $($path)*>,
+			$($bound)*
+		{
+			fn eq(&self, other: &DynEqRhs) -> bool {
+				<dyn $($path)* as ::core::cmp::PartialEq>::eq(self, other.as_plain())
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &DynEqRhs) -> bool {
+				<dyn $($path)* as ::core::cmp::PartialEq>::ne(self, other.as_plain())
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq for (dyn $($path)* + ::core::marker::Send + 'eq) where $($bound)* {
-			fn eq(&self, other: &Self) -> bool {
-				self.dyn_eq(DynEq::as_any(other))
+
+		$crate::__internal_eq_trait_object!(common ($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($marker)*));
+	};
+
+	// The impls that are the same for every marker combination.
+	(common ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> $crate::__private::SameTrait<dyn $($path)*> for (dyn $($path)* $($marker)*) where $($bound)* {
+			fn as_plain(&self) -> &(dyn $($path)*) {
+				self
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq for (dyn $($path)* + ::core::marker::Sync + 'eq) where $($bound)* {
-			fn eq(&self, other: &Self) -> bool {
-				self.dyn_eq(DynEq::as_any(other))
+
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::Eq for (dyn $($path)* $($marker)*) where $($bound)* {}
+
+		$($attrs)*
+		impl<$($generics)*> $crate::__private::TraitObject for (dyn $($path)* $($marker)*) where $($bound)* {}
+
+		$crate::__internal_eq_trait_object_alloc!(single ($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($marker)*));
+	};
+
+	// No more pairs of marker combinations.
+	(cross $attrs:tt $generics:tt $path:tt $bound:tt []) => {};
+
+	// Pair the first marker combination with every following one, then do the same for the others.
+	(cross $attrs:tt $generics:tt $path:tt $bound:tt [$first:tt $($rest:tt)*]) => {
+		$crate::__internal_eq_trait_object!(pairs $attrs $generics $path $bound $first [$($rest)*]);
+		$crate::__internal_eq_trait_object!(cross $attrs $generics $path $bound [$($rest)*]);
+	};
+
+	// No more marker combinations to pair with.
+	(pairs $attrs:tt $generics:tt $path:tt $bound:tt $first:tt []) => {};
+
+	// Implement in both directions for the first pair, then for the others. Trait objects are
+	// already handled by the generic impls, only their boxes need an impl per pair.
+	(pairs $attrs:tt $generics:tt $path:tt $bound:tt $first:tt [$second:tt $($rest:tt)*]) => {
+		$crate::__internal_eq_trait_object_alloc!(pair $attrs $generics $path $bound $first $second);
+		$crate::__internal_eq_trait_object_alloc!(pair $attrs $generics $path $bound $second $first);
+		$crate::__internal_eq_trait_object!(pairs $attrs $generics $path $bound $first [$($rest)*]);
+	};
+
+	// No more marker combinations for this pointer.
+	(pointer $attrs:tt $generics:tt $path:tt $bound:tt $pointer:tt []) => {};
+
+	// The impls between a pointer and its pointee, for the first marker combination, then for the
+	// others. Like for `Rc`, the pointer type is usually foreign so `PartialEq<&Self>` can't be
+	// implemented.
+	(pointer ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($pointer:tt)*) [($($marker:tt)*) $($rest:tt)*]) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<dyn $($path)* $($marker)*> for $($pointer)*<dyn $($path)* $($marker)*> where $($bound)* {
+			fn eq(&self, other: &(dyn $($path)* $($marker)*)) -> bool {
+				**self == *other
+			}
+		}
+
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$($pointer)*<dyn $($path)* $($marker)*>> for (dyn $($path)* $($marker)*) where $($bound)* {
+			fn eq(&self, other: &$($pointer)*<dyn $($path)* $($marker)*>) -> bool {
+				*self == **other
+			}
+		}
+
+		$crate::__internal_eq_trait_object!(pointer ($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($pointer)*) [$($rest)*]);
+	};
+}
+
+/// Implement [`PartialEq`] between the trait objects of two different traits that have
+/// [`DynEq`](crate::DynEq) as a supertrait.
+///
+/// Like for a single trait object, two values are equal if they have the same concrete type and
+/// are equal. The implementations are generated in both directions, for `dyn Trait`,
+/// `dyn Trait + Send`, `dyn Trait + Sync` and `dyn Trait + Send + Sync` (each only with the
+/// same markers), and when the `alloc` feature is enabled, for their boxed forms.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Request: DynEq {}
+/// trait CachedRequest: DynEq {}
+/// dyn_eq::cross_eq_trait_object!(Request, CachedRequest);
+///
+/// impl Request for u8 {}
+/// impl CachedRequest for u8 {}
+///
+/// let a: &dyn Request = &5u8;
+/// let b: &dyn CachedRequest = &5u8;
+/// assert!(a == b);
+/// assert!(b == a);
+/// ```
+#[macro_export]
+macro_rules! cross_eq_trait_object {
+	($left:path, $right:path $(,)?) => {
+		$crate::__internal_cross_eq_trait_object!($left, $right, [
+			()
+			(+ ::core::marker::Send)
+			(+ ::core::marker::Sync)
+			(+ ::core::marker::Send + ::core::marker::Sync)
+		]);
+	};
+}
+
+/// Internal implementation of [`cross_eq_trait_object`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_cross_eq_trait_object {
+	// No more marker combinations.
+	($left:path, $right:path, []) => {};
+
+	// Implement in both directions for the first marker combination, then for the others.
+	($left:path, $right:path, [($($marker:tt)*) $($rest:tt)*]) => {
+		impl<'eq, 'other> ::core::cmp::PartialEq<dyn $right $($marker)* + 'other> for (dyn $left $($marker)* + 'eq) {
+			fn eq(&self, other: &(dyn $right $($marker)* + 'other)) -> bool {
+				$crate::__private::cross_dyn_eq(self, other)
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &(dyn $right $($marker)* + 'other)) -> bool {
+				$crate::__private::cross_dyn_ne(self, other)
+			}
+		}
+		impl<'eq, 'other> ::core::cmp::PartialEq<dyn $left $($marker)* + 'other> for (dyn $right $($marker)* + 'eq) {
+			fn eq(&self, other: &(dyn $left $($marker)* + 'other)) -> bool {
+				$crate::__private::cross_dyn_eq(self, other)
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &(dyn $left $($marker)* + 'other)) -> bool {
+				$crate::__private::cross_dyn_ne(self, other)
+			}
+		}
+
+		$crate::__internal_cross_eq_trait_object_alloc!($left, $right, ($($marker)*));
+		$crate::__internal_cross_eq_trait_object!($left, $right, [$($rest)*]);
+	};
+}
+
+/// The implementations of [`cross_eq_trait_object`] and `upcast_eq_trait_object` for boxes.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_cross_eq_trait_object_alloc {
+	($left:path, $right:path, ($($marker:tt)*)) => {
+		impl<'eq, 'other> ::core::cmp::PartialEq<$crate::Box<dyn $right $($marker)* + 'other>> for $crate::Box<dyn $left $($marker)* + 'eq> {
+			fn eq(&self, other: &$crate::Box<dyn $right $($marker)* + 'other>) -> bool {
+				**self == **other
+			}
+		}
+		impl<'eq, 'other> ::core::cmp::PartialEq<$crate::Box<dyn $left $($marker)* + 'other>> for $crate::Box<dyn $right $($marker)* + 'eq> {
+			fn eq(&self, other: &$crate::Box<dyn $left $($marker)* + 'other>) -> bool {
+				**self == **other
+			}
+		}
+	};
+}
+
+/// When the `alloc` feature is disabled we don't do anything.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_cross_eq_trait_object_alloc {
+	($($tokens:tt)*) => {};
+}
+
+/// Implement [`PartialEq`] between the trait objects of a trait and of one of its supertraits.
+///
+/// The subtrait object is upcast to the supertrait object, which is then compared with the
+/// [`PartialEq`] implementation of the supertrait object, so both traits must be passed to
+/// [`eq_trait_object`] and the options of the supertrait (such as `cross_type(...)`) apply. The
+/// implementations are generated in both directions, for `dyn Trait`, `dyn Trait + Send`,
+/// `dyn Trait + Sync` and `dyn Trait + Send + Sync` (each only with the same markers), and when
+/// the `alloc` feature is enabled, for their boxed forms.
+///
+/// This requires the `trait-upcasting` feature.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Shape: DynEq {}
+/// dyn_eq::eq_trait_object!(Shape);
+///
+/// trait Polygon: Shape {}
+/// dyn_eq::eq_trait_object!(Polygon);
+/// dyn_eq::upcast_eq_trait_object!(Polygon: Shape);
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Square(u32);
+///
+/// impl Shape for Square {}
+/// impl Polygon for Square {}
+///
+/// let a: &dyn Polygon = &Square(1);
+/// let b: &dyn Shape = &Square(1);
+/// assert!(a == b);
+/// assert!(b == a);
+/// ```
+#[cfg(feature = "trait-upcasting")]
+#[macro_export]
+macro_rules! upcast_eq_trait_object {
+	($sub:path : $super:path $(,)?) => {
+		$crate::__internal_upcast_eq_trait_object!($sub, $super, [
+			()
+			(+ ::core::marker::Send)
+			(+ ::core::marker::Sync)
+			(+ ::core::marker::Send + ::core::marker::Sync)
+		]);
+	};
+}
+
+/// Internal implementation of [`upcast_eq_trait_object`].
+#[cfg(feature = "trait-upcasting")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_upcast_eq_trait_object {
+	// No more marker combinations.
+	($sub:path, $super:path, []) => {};
+
+	// Implement in both directions for the first marker combination, then for the others.
+	($sub:path, $super:path, [($($marker:tt)*) $($rest:tt)*]) => {
+		impl<'eq, 'other> ::core::cmp::PartialEq<dyn $super $($marker)* + 'other> for (dyn $sub $($marker)* + 'eq) {
+			fn eq(&self, other: &(dyn $super $($marker)* + 'other)) -> bool {
+				let this: &(dyn $super $($marker)* + 'eq) = self;
+				*this == *other
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &(dyn $super $($marker)* + 'other)) -> bool {
+				let this: &(dyn $super $($marker)* + 'eq) = self;
+				*this != *other
+			}
+		}
+		impl<'eq, 'other> ::core::cmp::PartialEq<dyn $sub $($marker)* + 'other> for (dyn $super $($marker)* + 'eq) {
+			fn eq(&self, other: &(dyn $sub $($marker)* + 'other)) -> bool {
+				let other: &(dyn $super $($marker)* + 'other) = other;
+				*self == *other
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &(dyn $sub $($marker)* + 'other)) -> bool {
+				let other: &(dyn $super $($marker)* + 'other) = other;
+				*self != *other
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq for (dyn $($path)* + ::core::marker::Send + ::core::marker::Sync + 'eq) where $($bound)* {
+
+		$crate::__internal_cross_eq_trait_object_alloc!($sub, $super, ($($marker)*));
+		$crate::__internal_upcast_eq_trait_object!($sub, $super, [$($rest)*]);
+	};
+}
+
+/// Implement [`PartialEq`] in both directions between a trait object and an enum whose variants
+/// each wrap a type implementing the trait, such as the enums of the `enum_dispatch` crate.
+///
+/// The enum is compared with the trait object as if its value was boxed as a `dyn Trait`, so
+/// code using both forms of the same values gets the same results. The implementations are
+/// generated for `dyn Trait`, and when the `alloc` feature is enabled, for `Box<dyn Trait>`.
+///
+/// Each listed variant must be a tuple variant with a single field, whose type implements the
+/// trait.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Shape: DynEq {}
+/// dyn_eq::eq_trait_object!(Shape);
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Circle(u32);
+/// #[derive(PartialEq, Eq)]
+/// struct Square(u32);
+///
+/// impl Shape for Circle {}
+/// impl Shape for Square {}
+///
+/// enum AnyShape {
+///     Circle(Circle),
+///     Square(Square),
+/// }
+/// dyn_eq::enum_eq_trait_object!(Shape, AnyShape { Circle, Square });
+///
+/// let shape: &dyn Shape = &Circle(1);
+/// assert!(*shape == AnyShape::Circle(Circle(1)));
+/// assert!(AnyShape::Square(Square(1)) != *shape);
+/// ```
+#[macro_export]
+macro_rules! enum_eq_trait_object {
+	($trait:path, $enum:path { $($variant:ident),+ $(,)? } $(,)?) => {
+		impl<'eq> ::core::cmp::PartialEq<dyn $trait + 'eq> for $enum {
+			fn eq(&self, other: &(dyn $trait + 'eq)) -> bool {
+				let this: &(dyn $trait + 'eq) = match self {
+					$(Self::$variant(value) => value,)+
+				};
+				*this == *other
+			}
+		}
+
+		impl<'eq> ::core::cmp::PartialEq<$enum> for (dyn $trait + 'eq) {
+			fn eq(&self, other: &$enum) -> bool {
+				*other == *self
+			}
+		}
+
+		$crate::__internal_enum_eq_trait_object_alloc!($trait, $enum);
+	};
+}
+
+/// The implementations of [`enum_eq_trait_object`] for boxes.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_enum_eq_trait_object_alloc {
+	($trait:path, $enum:path) => {
+		impl<'eq> ::core::cmp::PartialEq<$enum> for $crate::Box<dyn $trait + 'eq> {
+			fn eq(&self, other: &$enum) -> bool {
+				**self == *other
+			}
+		}
+
+		impl<'eq> ::core::cmp::PartialEq<$crate::Box<dyn $trait + 'eq>> for $enum {
+			fn eq(&self, other: &$crate::Box<dyn $trait + 'eq>) -> bool {
+				**other == *self
+			}
+		}
+	};
+}
+
+/// When the `alloc` feature is disabled we don't do anything.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_enum_eq_trait_object_alloc {
+	($($tokens:tt)*) => {};
+}
+
+/// Implement [`PartialEq`] and [`Eq`] for a custom pointer to a trait object that has
+/// [`DynEq`](crate::DynEq) as a supertrait.
+///
+/// The pointer must be a type of the current crate that implements
+/// [`Deref`](core::ops::Deref) to the trait object, takes it as its only type parameter and
+/// doesn't already implement [`PartialEq`]. Pointers are compared by the values they point to,
+/// like `Box<dyn Trait>`: they can be compared with each other and with the trait object, and
+/// used in structs deriving [`PartialEq`] and [`Eq`]. The implementations are generated for
+/// `dyn Trait`, `dyn Trait + Send`, `dyn Trait + Sync` and `dyn Trait + Send + Sync`.
+///
+/// [`eq_trait_object`] must have been invoked for the trait. For foreign pointers, use its
+/// `pointers(...)` option instead.
+///
+/// # Example
+///
+/// ```
+/// use std::ops::Deref;
+///
+/// use dyn_eq::DynEq;
+///
+/// pub struct Handle<T: ?Sized>(Box<T>);
+///
+/// impl<T: ?Sized> Deref for Handle<T> {
+///     type Target = T;
+///
+///     fn deref(&self) -> &T {
+///         &self.0
+///     }
+/// }
+///
+/// trait Node: DynEq {}
+/// dyn_eq::eq_trait_object!(Node);
+/// dyn_eq::deref_eq_trait_object!(Node, Handle);
+///
+/// impl Node for u8 {}
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Tree {
+///     root: Handle<dyn Node>,
+/// }
+///
+/// let a = Tree {
+///     root: Handle(Box::new(1u8)),
+/// };
+/// let b = Tree {
+///     root: Handle(Box::new(1u8)),
+/// };
+/// assert!(a == b);
+/// ```
+#[macro_export]
+macro_rules! deref_eq_trait_object {
+	($trait:path, $($pointer:ident)::+ $(,)?) => {
+		$crate::__internal_deref_eq_trait_object!(begin ($trait) ($($pointer)::+) [
+			()
+			(+ ::core::marker::Send)
+			(+ ::core::marker::Sync)
+			(+ ::core::marker::Send + ::core::marker::Sync)
+		]);
+	};
+}
+
+/// Internal implementation of [`deref_eq_trait_object`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_deref_eq_trait_object {
+	// Implement everything for the given marker combinations.
+	(begin ($($path:tt)*) $pointer:tt $markers:tt) => {
+		$crate::__internal_deref_eq_trait_object!(each ($($path)*) $pointer $markers);
+		$crate::__internal_deref_eq_trait_object!(cross ($($path)*) $pointer $markers);
+		$crate::__internal_eq_trait_object!(pointer () ('eq,) ($($path)* + 'eq) () $pointer $markers);
+	};
+
+	// No more marker combinations.
+	(each $path:tt $pointer:tt []) => {};
+
+	// The impls for the first marker combination, then for the others.
+	(each ($($path:tt)*) ($($pointer:tt)*) [($($marker:tt)*) $($rest:tt)*]) => {
+		impl<'eq> ::core::cmp::PartialEq for $($pointer)*<dyn $($path)* $($marker)* + 'eq> {
 			fn eq(&self, other: &Self) -> bool {
-				self.dyn_eq(DynEq::as_any(other))
+				**self == **other
+			}
+
+			#[allow(clippy::partialeq_ne_impl)]
+			fn ne(&self, other: &Self) -> bool {
+				**self != **other
+			}
+		}
+
+		impl<'eq> ::core::cmp::PartialEq<&Self> for $($pointer)*<dyn $($path)* $($marker)* + 'eq> {
+			fn eq(&self, other: &&Self) -> bool {
+				self == *other
 			}
 		}
 
-		$crate::__internal_eq_trait_object_alloc!(($($generics)*) ($($path)*) ($($bound)*));
+		impl<'eq> ::core::cmp::Eq for $($pointer)*<dyn $($path)* $($marker)* + 'eq> {}
+
+		$crate::__internal_deref_eq_trait_object!(each ($($path)*) ($($pointer)*) [$($rest)*]);
+	};
 
-		impl<'eq, $($generics)*> ::core::cmp::Eq for (dyn $($path)* + 'eq) where $($bound)* {}
-		impl<'eq, $($generics)*> ::core::cmp::Eq for (dyn $($path)* + ::core::marker::Send + 'eq) where $($bound)* {}
-		impl<'eq, $($generics)*> ::core::cmp::Eq for (dyn $($path)* + ::core::marker::Sync + 'eq) where $($bound)* {}
-		impl<'eq, $($generics)*> ::core::cmp::Eq for (dyn $($path)* + ::core::marker::Send + ::core::marker::Sync + 'eq) where $($bound)* {}
+	// No more pairs of marker combinations.
+	(cross $path:tt $pointer:tt []) => {};
+
+	// Pair the first marker combination with every following one, then do the same for the others.
+	(cross $path:tt $pointer:tt [$first:tt $($rest:tt)*]) => {
+		$crate::__internal_deref_eq_trait_object!(pairs $path $pointer $first [$($rest)*]);
+		$crate::__internal_deref_eq_trait_object!(cross $path $pointer [$($rest)*]);
+	};
+
+	// No more marker combinations to pair with.
+	(pairs $path:tt $pointer:tt $first:tt []) => {};
+
+	// Implement in both directions for the first pair, then for the others.
+	(pairs $path:tt $pointer:tt $first:tt [$second:tt $($rest:tt)*]) => {
+		$crate::__internal_deref_eq_trait_object!(pair $path $pointer $first $second);
+		$crate::__internal_deref_eq_trait_object!(pair $path $pointer $second $first);
+		$crate::__internal_deref_eq_trait_object!(pairs $path $pointer $first [$($rest)*]);
+	};
+
+	// The impl between two different marker combinations.
+	(pair ($($path:tt)*) ($($pointer:tt)*) ($($left:tt)*) ($($right:tt)*)) => {
+		impl<'eq, 'other> ::core::cmp::PartialEq<$($pointer)*<dyn $($path)* $($right)* + 'other>> for $($pointer)*<dyn $($path)* $($left)* + 'eq> {
+			fn eq(&self, other: &$($pointer)*<dyn $($path)* $($right)* + 'other>) -> bool {
+				**self == **other
+			}
+		}
 	};
 }
 
@@ -121,34 +1148,189 @@ macro_rules! __internal_eq_trait_object {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __internal_eq_trait_object_alloc {
-	(($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* + 'eq> where $($bound)* {
-			fn eq(&self, other: &&Self) -> bool {
-				self == *other
+	(single ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$crate::__internal_eq_trait_object_box!(single ($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($marker)*));
+		$crate::__internal_eq_trait_object_rc!(($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($marker)*));
+		$crate::__internal_eq_trait_object_arc!(($($attrs)*) ($($generics)*) ($($path)*) ($($bound)*) ($($marker)*));
+	};
+
+	(pair $attrs:tt $generics:tt $path:tt $bound:tt $left:tt $right:tt) => {
+		$crate::__internal_eq_trait_object_box!(pair $attrs $generics $path $bound $left $right);
+	};
+
+	// No more marker combinations.
+	(mixed $attrs:tt $generics:tt $path:tt $bound:tt []) => {};
+
+	// The impls between the different kinds of pointers, for the first marker combination, then
+	// for the others.
+	(mixed $attrs:tt $generics:tt ($($path:tt)*) $bound:tt [($($marker:tt)*) $($rest:tt)*]) => {
+		$crate::__internal_eq_trait_object_alloc!(mixed_pair $attrs $generics $bound ($crate::Box<dyn $($path)* $($marker)*>) (&(dyn $($path)* $($marker)*)));
+		$crate::__internal_eq_trait_object_rc!(mixed $attrs $generics ($($path)*) $bound ($($marker)*));
+		$crate::__internal_eq_trait_object_arc!(mixed $attrs $generics ($($path)*) $bound ($($marker)*));
+		$crate::__internal_eq_trait_object_alloc!(mixed $attrs $generics ($($path)*) $bound [$($rest)*]);
+	};
+
+	// Implement in both directions between two pointers to the same trait object.
+	(mixed_pair ($($attrs:tt)*) ($($generics:tt)*) ($($bound:tt)*) ($($left:tt)*) ($($right:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$($right)*> for $($left)* where $($bound)* {
+			fn eq(&self, other: &$($right)*) -> bool {
+				**self == **other
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* + ::core::marker::Send + 'eq> where $($bound)* {
-			fn eq(&self, other: &&Self) -> bool {
-				self == *other
+
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$($left)*> for $($right)* where $($bound)* {
+			fn eq(&self, other: &$($left)*) -> bool {
+				**self == **other
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* + ::core::marker::Sync + 'eq> where $($bound)* {
+	};
+}
+
+/// When the `alloc` feature is disabled we don't do anything.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_eq_trait_object_alloc {
+	($($tokens:tt)*) => {};
+}
+
+/// The impls of `Box`, for the default allocator.
+#[cfg(all(feature = "alloc", not(feature = "allocator-api")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_eq_trait_object_box {
+	(single ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* $($marker)*> where $($bound)* {
 			fn eq(&self, other: &&Self) -> bool {
 				self == *other
 			}
 		}
-		impl<'eq, $($generics)*> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* + ::core::marker::Send + ::core::marker::Sync + 'eq> where $($bound)* {
+
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<dyn $($path)* $($marker)*> for $crate::Box<dyn $($path)* $($marker)*> where $($bound)* {
+			fn eq(&self, other: &(dyn $($path)* $($marker)*)) -> bool {
+				**self == *other
+			}
+		}
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$crate::Box<dyn $($path)* $($marker)*>> for (dyn $($path)* $($marker)*) where $($bound)* {
+			fn eq(&self, other: &$crate::Box<dyn $($path)* $($marker)*>) -> bool {
+				*self == **other
+			}
+		}
+	};
+
+	(pair ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($left:tt)*) ($($right:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$crate::Box<dyn $($path)* $($right)*>> for $crate::Box<dyn $($path)* $($left)*> where $($bound)* {
+			fn eq(&self, other: &$crate::Box<dyn $($path)* $($right)*>) -> bool {
+				**self == **other
+			}
+		}
+	};
+}
+
+/// The impls of `Box`, for any allocator.
+///
+/// Trait objects are only compared with boxes using the global allocator, since a generic impl
+/// would overlap with the one over `SameTrait` for the traits of this crate.
+#[cfg(all(feature = "alloc", feature = "allocator-api"))]
+#[doc(hidden)]
+#[macro_export]
+#[allow_internal_unstable(allocator_api)]
+macro_rules! __internal_eq_trait_object_box {
+	(single ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)* DynEqAlloc: $crate::__private::Allocator> ::core::cmp::PartialEq<&Self> for $crate::Box<dyn $($path)* $($marker)*, DynEqAlloc> where $($bound)* {
 			fn eq(&self, other: &&Self) -> bool {
 				self == *other
 			}
 		}
-	}
+
+		$($attrs)*
+		impl<$($generics)* DynEqAlloc: $crate::__private::Allocator> ::core::cmp::PartialEq<dyn $($path)* $($marker)*> for $crate::Box<dyn $($path)* $($marker)*, DynEqAlloc> where $($bound)* {
+			fn eq(&self, other: &(dyn $($path)* $($marker)*)) -> bool {
+				**self == *other
+			}
+		}
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<$crate::Box<dyn $($path)* $($marker)*>> for (dyn $($path)* $($marker)*) where $($bound)* {
+			fn eq(&self, other: &$crate::Box<dyn $($path)* $($marker)*>) -> bool {
+				*self == **other
+			}
+		}
+	};
+
+	(pair ($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($left:tt)*) ($($right:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)* DynEqAlloc: $crate::__private::Allocator, DynEqAllocRhs: $crate::__private::Allocator> ::core::cmp::PartialEq<$crate::Box<dyn $($path)* $($right)*, DynEqAllocRhs>> for $crate::Box<dyn $($path)* $($left)*, DynEqAlloc> where $($bound)* {
+			fn eq(&self, other: &$crate::Box<dyn $($path)* $($right)*, DynEqAllocRhs>) -> bool {
+				**self == **other
+			}
+		}
+	};
 }
 
-/// When the `alloc` feature is disabled we don't do anything.
-#[cfg(not(feature = "alloc"))]
+// `Rc` and `Arc` aren't `#[fundamental]`, so we can't implement `PartialEq<&Self>` for them. Any
+// other `PartialEq` implementation is enough to work around the issue, and comparing with the
+// pointee is the most useful one.
+
+/// The code to fix [this](https://github.com/rust-lang/rust/issues/31740) issue for `Rc`.
+#[cfg(feature = "rc")]
 #[doc(hidden)]
 #[macro_export]
-macro_rules! __internal_eq_trait_object_alloc {
-	(($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {};
+macro_rules! __internal_eq_trait_object_rc {
+	(mixed $attrs:tt $generics:tt ($($path:tt)*) $bound:tt ($($marker:tt)*)) => {
+		$crate::__internal_eq_trait_object_alloc!(mixed_pair $attrs $generics $bound ($crate::Rc<dyn $($path)* $($marker)*>) (&(dyn $($path)* $($marker)*)));
+		$crate::__internal_eq_trait_object_alloc!(mixed_pair $attrs $generics $bound ($crate::Rc<dyn $($path)* $($marker)*>) ($crate::Box<dyn $($path)* $($marker)*>));
+	};
+
+	(($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<dyn $($path)* $($marker)*> for $crate::Rc<dyn $($path)* $($marker)*> where $($bound)* {
+			fn eq(&self, other: &(dyn $($path)* $($marker)*)) -> bool {
+				**self == *other
+			}
+		}
+	};
+}
+
+/// When the `rc` feature is disabled we don't do anything.
+#[cfg(not(feature = "rc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_eq_trait_object_rc {
+	($($tokens:tt)*) => {};
+}
+
+/// The code to fix [this](https://github.com/rust-lang/rust/issues/31740) issue for `Arc`.
+#[cfg(all(feature = "arc", target_has_atomic = "ptr"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_eq_trait_object_arc {
+	(mixed $attrs:tt $generics:tt ($($path:tt)*) $bound:tt ($($marker:tt)*)) => {
+		$crate::__internal_eq_trait_object_alloc!(mixed_pair $attrs $generics $bound ($crate::Arc<dyn $($path)* $($marker)*>) (&(dyn $($path)* $($marker)*)));
+		$crate::__internal_eq_trait_object_alloc!(mixed_pair $attrs $generics $bound ($crate::Arc<dyn $($path)* $($marker)*>) ($crate::Box<dyn $($path)* $($marker)*>));
+	};
+
+	(($($attrs:tt)*) ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) ($($marker:tt)*)) => {
+		$($attrs)*
+		impl<$($generics)*> ::core::cmp::PartialEq<dyn $($path)* $($marker)*> for $crate::Arc<dyn $($path)* $($marker)*> where $($bound)* {
+			fn eq(&self, other: &(dyn $($path)* $($marker)*)) -> bool {
+				**self == *other
+			}
+		}
+	};
+}
+
+/// When the `arc` feature is disabled (or when the target doesn't support `Arc`) we don't do
+/// anything.
+#[cfg(not(all(feature = "arc", target_has_atomic = "ptr")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_eq_trait_object_arc {
+	($($tokens:tt)*) => {};
 }
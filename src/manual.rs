@@ -0,0 +1,82 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Custom equality semantics for trait objects.
+
+use core::ops::{Deref, DerefMut};
+
+/// Equality used by [`Manual`] instead of [`Eq`].
+///
+/// Like [`Eq`], it must be an equivalence relation (reflexive, symmetric and transitive).
+pub trait ManualEq: 'static {
+	/// Tests if `self` and `other` are equal.
+	fn manual_eq(&self, other: &Self) -> bool;
+}
+
+/// Wrapper comparing its content with [`ManualEq`] instead of [`Eq`].
+///
+/// Every type implementing [`Eq`] implements [`DynEq`](crate::DynEq) using it, so a type can't
+/// provide its own implementation. This wrapper allows to give different semantics to the
+/// comparison of trait objects (e.g. ignoring a cache field), or to compare types that don't
+/// implement [`Eq`] at all.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use dyn_eq::{DynEq, Manual, ManualEq};
+///
+/// trait Rule: DynEq {}
+/// dyn_eq::eq_trait_object!(Rule);
+///
+/// struct CachedRule {
+///     pattern: String,
+///     hits: Cell<usize>,
+/// }
+///
+/// impl ManualEq for CachedRule {
+///     fn manual_eq(&self, other: &Self) -> bool {
+///         self.pattern == other.pattern
+///     }
+/// }
+///
+/// impl Rule for Manual<CachedRule> {}
+///
+/// let a: &dyn Rule = &Manual(CachedRule {
+///     pattern: "*.rs".to_owned(),
+///     hits: Cell::new(1),
+/// });
+/// let b: &dyn Rule = &Manual(CachedRule {
+///     pattern: "*.rs".to_owned(),
+///     hits: Cell::new(5),
+/// });
+/// assert!(a == b);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Manual<T>(pub T);
+
+impl<T> Deref for Manual<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Manual<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: ManualEq> PartialEq for Manual<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.manual_eq(&other.0)
+	}
+}
+
+impl<T: ManualEq> Eq for Manual<T> {}
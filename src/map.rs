@@ -0,0 +1,213 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Maps keyed by trait objects.
+
+use alloc::vec::Vec;
+
+use crate::Box;
+
+/// A map whose keys are boxed values, such as `Box<dyn Trait>`.
+///
+/// This works on any [`PartialEq`] key type, which includes `dyn Trait` once
+/// [`eq_trait_object`](crate::eq_trait_object) has been invoked for `Trait`. Since trait objects
+/// can't be hashed, each lookup compares the key with every key of the map, which takes `O(n)`
+/// comparisons for a map of `n` entries.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynEq, DynMap};
+///
+/// trait ConfigKey: DynEq {}
+/// dyn_eq::eq_trait_object!(ConfigKey);
+///
+/// impl ConfigKey for u8 {}
+/// impl ConfigKey for &'static str {}
+///
+/// let mut config: DynMap<dyn ConfigKey, u32> = DynMap::new();
+/// config.insert(Box::new(1u8), 10);
+/// *config.entry(Box::new("retries")).or_insert_with(|| 3) += 1;
+///
+/// assert_eq!(config.get(&1u8), Some(&10));
+/// assert_eq!(config.get(&"retries"), Some(&4));
+/// assert_eq!(config.get(&2u8), None);
+/// ```
+#[derive(Debug)]
+pub struct DynMap<K: ?Sized, V> {
+	/// The entries, in the order in which their key was first inserted.
+	entries: Vec<(Box<K>, V)>,
+}
+
+impl<K: ?Sized, V> DynMap<K, V> {
+	/// Creates an empty map.
+	pub const fn new() -> Self {
+		Self {
+			entries: Vec::new(),
+		}
+	}
+
+	/// Returns the number of entries.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns `true` if the map has no entry.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Iterates over the keys and the values, in the order in which the keys were first inserted.
+	pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+		self.entries.iter().map(|(key, value)| (&**key, value))
+	}
+
+	/// Iterates over the keys and mutable references to the values, in the order in which the
+	/// keys were first inserted.
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+		self.entries.iter_mut().map(|(key, value)| (&**key, value))
+	}
+
+	/// Iterates over the keys, in the order in which they were first inserted.
+	pub fn keys(&self) -> impl Iterator<Item = &K> {
+		self.entries.iter().map(|(key, _)| &**key)
+	}
+
+	/// Iterates over the values, in the order in which their key was first inserted.
+	pub fn values(&self) -> impl Iterator<Item = &V> {
+		self.entries.iter().map(|(_, value)| value)
+	}
+}
+
+impl<K: ?Sized + PartialEq, V> DynMap<K, V> {
+	/// Returns the position of the entry whose key is equal to `key`.
+	fn position(&self, key: &K) -> Option<usize> {
+		self.entries.iter().position(|(other, _)| **other == *key)
+	}
+
+	/// Returns the value of the key equal to `key`, or `None` if there is none.
+	pub fn get(&self, key: &K) -> Option<&V> {
+		self.position(key).map(|i| &self.entries[i].1)
+	}
+
+	/// Returns a mutable reference to the value of the key equal to `key`, or `None` if there is
+	/// none.
+	pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+		self.position(key).map(|i| &mut self.entries[i].1)
+	}
+
+	/// Returns `true` if the map has a key equal to `key`.
+	pub fn contains_key(&self, key: &K) -> bool {
+		self.position(key).is_some()
+	}
+
+	/// Inserts a value, and returns the previous value of the key.
+	///
+	/// If the map already has a key equal to `key`, this key is kept and `key` is dropped.
+	pub fn insert(&mut self, key: Box<K>, value: V) -> Option<V> {
+		match self.position(&key) {
+			Some(i) => Some(core::mem::replace(&mut self.entries[i].1, value)),
+			None => {
+				self.entries.push((key, value));
+				None
+			}
+		}
+	}
+
+	/// Removes the entry whose key is equal to `key`, and returns its value.
+	///
+	/// The order of the remaining entries is kept.
+	pub fn remove(&mut self, key: &K) -> Option<V> {
+		self.position(key).map(|i| self.entries.remove(i).1)
+	}
+
+	/// Returns the entry of `key`, to read or insert its value in place.
+	pub fn entry(&mut self, key: Box<K>) -> Entry<'_, K, V> {
+		let index = self.position(&key);
+		Entry {
+			map: self,
+			key,
+			index,
+		}
+	}
+}
+
+impl<K: ?Sized, V> Default for DynMap<K, V> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K: ?Sized + PartialEq, V> Extend<(Box<K>, V)> for DynMap<K, V> {
+	fn extend<I: IntoIterator<Item = (Box<K>, V)>>(&mut self, iter: I) {
+		for (key, value) in iter {
+			self.insert(key, value);
+		}
+	}
+}
+
+impl<K: ?Sized + PartialEq, V> FromIterator<(Box<K>, V)> for DynMap<K, V> {
+	fn from_iter<I: IntoIterator<Item = (Box<K>, V)>>(iter: I) -> Self {
+		let mut map = Self::new();
+		map.extend(iter);
+		map
+	}
+}
+
+/// The entry of a key in a [`DynMap`], returned by [`DynMap::entry`].
+#[derive(Debug)]
+pub struct Entry<'a, K: ?Sized, V> {
+	/// The map containing the entry.
+	map: &'a mut DynMap<K, V>,
+	/// The key of the entry, dropped if the map already has an equal key.
+	key: Box<K>,
+	/// The position of the entry in the map, if the key is already in the map.
+	index: Option<usize>,
+}
+
+impl<'a, K: ?Sized, V> Entry<'a, K, V> {
+	/// Returns the key of the entry.
+	pub fn key(&self) -> &K {
+		&self.key
+	}
+
+	/// Returns the value of the entry, or `None` if the key isn't in the map.
+	pub fn get(&self) -> Option<&V> {
+		self.index.map(|i| &self.map.entries[i].1)
+	}
+
+	/// Returns the value of the entry, inserting `default` first if the key isn't in the map.
+	pub fn or_insert(self, default: V) -> &'a mut V {
+		self.or_insert_with(|| default)
+	}
+
+	/// Returns the value of the entry, inserting the result of `default` first if the key isn't in
+	/// the map.
+	pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+		let map = self.map;
+		let i = self.index.unwrap_or_else(|| {
+			map.entries.push((self.key, default()));
+			map.entries.len() - 1
+		});
+		&mut map.entries[i].1
+	}
+
+	/// Returns the value of the entry, inserting the default value first if the key isn't in the
+	/// map.
+	pub fn or_default(self) -> &'a mut V
+	where
+		V: Default,
+	{
+		self.or_insert_with(V::default)
+	}
+
+	/// Calls `f` on the value of the entry if the key is in the map, and returns the entry.
+	pub fn and_modify(self, f: impl FnOnce(&mut V)) -> Self {
+		if let Some(i) = self.index {
+			f(&mut self.map.entries[i].1);
+		}
+		self
+	}
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Patterns that trait objects can be matched against, for tests and rule engines.
+//!
+//! A matcher isn't equal to the values it matches: it matches values of two different types that
+//! aren't equal to each other, so an equality with these values wouldn't be transitive. The
+//! matchers are instead tested against trait objects with their `matches` method, and the
+//! `cross_type(...)` option of [`eq_trait_object`](crate::eq_trait_object) should only be used
+//! for types that are really equivalent.
+//!
+//! # Example
+//!
+//! ```
+//! use dyn_eq::{
+//!     DynEq,
+//!     matchers::{AnyOfType, Predicate},
+//! };
+//!
+//! trait Event: DynEq {}
+//! dyn_eq::eq_trait_object!(Event);
+//!
+//! impl Event for u8 {}
+//! impl Event for u16 {}
+//!
+//! let any_u8 = AnyOfType::<u8>::new();
+//! let even = Predicate::new(|value: &u16| value % 2 == 0);
+//!
+//! assert!(any_u8.matches(&(&5u8 as &dyn Event)));
+//! assert!(!any_u8.matches(&(&5u16 as &dyn Event)));
+//! assert!(even.matches_dyn(&(&4u16 as &dyn Event)));
+//! assert!(!even.matches_dyn(&(&5u16 as &dyn Event)));
+//! assert!(!even.matches_dyn(&(&4u8 as &dyn Event)));
+//! ```
+
+use core::{
+	any::{Any, type_name},
+	fmt::{self, Debug},
+	marker::PhantomData,
+	ops::Deref,
+};
+
+use crate::DynEq;
+
+/// A matcher that matches every value of type `T`.
+///
+/// Two of these matchers are always equal.
+pub struct AnyOfType<T: ?Sized>(PhantomData<fn() -> T>);
+
+impl<T: ?Sized> AnyOfType<T> {
+	/// Creates the matcher.
+	pub const fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Any> AnyOfType<T> {
+	/// Returns `true` if the value pointed to by `value`, such as a `&dyn Trait` or a
+	/// `Box<dyn Trait>`, is of type `T`.
+	pub fn matches(&self, value: &impl Deref<Target = impl ?Sized + DynEq>) -> bool {
+		crate::is::<T>(value)
+	}
+}
+
+impl<T: ?Sized> Default for AnyOfType<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: ?Sized> Clone for AnyOfType<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T: ?Sized> Copy for AnyOfType<T> {}
+
+impl<T: ?Sized> Debug for AnyOfType<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "AnyOfType<{}>", type_name::<T>())
+	}
+}
+
+impl<T: ?Sized> PartialEq for AnyOfType<T> {
+	fn eq(&self, _other: &Self) -> bool {
+		true
+	}
+}
+
+impl<T: ?Sized> Eq for AnyOfType<T> {}
+
+/// A matcher that matches every value of type `T` for which a function returns `true`.
+///
+/// Two predicates are equal if they use the same function.
+pub struct Predicate<T: ?Sized>(fn(&T) -> bool);
+
+impl<T: ?Sized> Predicate<T> {
+	/// Creates the matcher. Closures that don't capture anything can be used.
+	pub const fn new(predicate: fn(&T) -> bool) -> Self {
+		Self(predicate)
+	}
+
+	/// Returns `true` if `value` matches.
+	pub fn matches(&self, value: &T) -> bool {
+		(self.0)(value)
+	}
+}
+
+impl<T: Any> Predicate<T> {
+	/// Returns `true` if the value pointed to by `value`, such as a `&dyn Trait` or a
+	/// `Box<dyn Trait>`, is of type `T` and matches.
+	pub fn matches_dyn(&self, value: &impl Deref<Target = impl ?Sized + DynEq>) -> bool {
+		crate::downcast_ref::<T>(value).is_some_and(|value| self.matches(value))
+	}
+}
+
+impl<T: ?Sized> Clone for Predicate<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T: ?Sized> Copy for Predicate<T> {}
+
+impl<T: ?Sized> Debug for Predicate<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Predicate<{}>", type_name::<T>())
+	}
+}
+
+impl<T: ?Sized> PartialEq for Predicate<T> {
+	fn eq(&self, other: &Self) -> bool {
+		core::ptr::fn_addr_eq(self.0, other.0)
+	}
+}
+
+impl<T: ?Sized> Eq for Predicate<T> {}
@@ -0,0 +1,20 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Re-exports of the commonly used traits and macros.
+//!
+//! ```
+//! use dyn_eq::prelude::*;
+//!
+//! trait MyTrait: DynEq {}
+//! eq_trait_object!(MyTrait);
+//!
+//! impl MyTrait for u8 {}
+//!
+//! let value: &dyn MyTrait = &5u8;
+//! assert!(value.eq_concrete(&5u8));
+//! ```
+
+pub use crate::{DynEq, DynEqExt, eq_trait_object};
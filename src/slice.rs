@@ -0,0 +1,95 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers to search a concrete value in slices of trait objects, and to compare slices.
+//!
+//! The elements can be any pointer to a trait object (`&dyn Trait`, `Box<dyn Trait>`,
+//! `Rc<dyn Trait>`, ...), and the searched value doesn't need to be boxed.
+
+use core::ops::Deref;
+
+use crate::DynEq;
+
+/// Returns `true` if the slice contains an element of type `T` equal to `value`.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait MyTrait: DynEq {}
+/// impl MyTrait for u8 {}
+/// impl MyTrait for u16 {}
+///
+/// let values: [&dyn MyTrait; 2] = [&1u8, &2u16];
+///
+/// assert!(dyn_eq::slice::contains(&values, &2u16));
+/// assert!(!dyn_eq::slice::contains(&values, &2u8));
+/// ```
+pub fn contains<P, T>(slice: &[P], value: &T) -> bool
+where
+	P: Deref,
+	P::Target: DynEq,
+	T: Eq + 'static,
+{
+	position_of(slice, value).is_some()
+}
+
+/// Returns the index of the first element of type `T` equal to `value`.
+pub fn position_of<P, T>(slice: &[P], value: &T) -> Option<usize>
+where
+	P: Deref,
+	P::Target: DynEq,
+	T: Eq + 'static,
+{
+	slice
+		.iter()
+		.position(|element| crate::eq(&**element, value))
+}
+
+/// Returns `true` if both slices contain the same values, in any order.
+///
+/// This is a multiset comparison: each value must appear the same number of times in both
+/// slices. The elements of the two slices can be different pointers to trait objects, even of
+/// different traits. Since trait objects can't be hashed, the number of comparisons is quadratic
+/// in the length of the slices.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Effect: DynEq {}
+/// impl Effect for u8 {}
+/// impl Effect for u16 {}
+///
+/// let expected: [&dyn Effect; 3] = [&1u8, &2u16, &1u8];
+/// let actual: Vec<Box<dyn Effect>> = vec![Box::new(2u16), Box::new(1u8), Box::new(1u8)];
+///
+/// assert!(dyn_eq::slice::set_eq(&expected, &actual));
+/// assert!(!dyn_eq::slice::set_eq(&expected, &actual[..2]));
+/// ```
+pub fn set_eq<P, Q>(a: &[P], b: &[Q]) -> bool
+where
+	P: Deref,
+	P::Target: DynEq,
+	Q: Deref,
+	Q::Target: DynEq,
+{
+	// Counting instead of marking the matched elements avoids an allocation
+	a.len() == b.len()
+		&& a.iter().all(|value| {
+			let value = &**value;
+			let count_a = a
+				.iter()
+				.filter(|other| (**other).dyn_eq(value.as_any()))
+				.count();
+			let count_b = b
+				.iter()
+				.filter(|other| (**other).dyn_eq(value.as_any()))
+				.count();
+			count_a == count_b
+		})
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Runtime checks of the equality laws, enabled by the `strict-checks` feature.
+
+use core::{any::type_name, cell::Cell};
+
+std::thread_local! {
+	/// The number of comparisons of this thread that are running.
+	static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts a running comparison until it's dropped.
+struct Running;
+
+impl Running {
+	/// Starts a comparison, and returns whether it's the outermost one of this thread.
+	fn start() -> (Self, bool) {
+		let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+		(Self, depth == 0)
+	}
+}
+
+impl Drop for Running {
+	fn drop(&mut self) {
+		DEPTH.with(|depth| depth.set(depth.get() - 1));
+	}
+}
+
+/// Tests if `this` and `other` are equal, and checks the laws if this is the outermost
+/// comparison.
+///
+/// Comparing values that contain trait objects compares these trait objects too, and checking
+/// each of them would multiply the number of comparisons at every level of nesting. The values
+/// compared by the outermost comparison are still fully checked, since the checks compare them
+/// entirely.
+#[track_caller]
+pub fn eq<T: PartialEq>(this: &T, other: &T) -> bool {
+	if !cfg!(debug_assertions) {
+		return this == other;
+	}
+
+	let (_running, outermost) = Running::start();
+	let equal = this == other;
+	if outermost {
+		check(this, other, equal);
+	}
+	equal
+}
+
+/// Tests if `this` and `other` are different, and checks the laws if this is the outermost
+/// comparison, like [`eq`].
+#[track_caller]
+pub fn ne<T: PartialEq>(this: &T, other: &T) -> bool {
+	if !cfg!(debug_assertions) {
+		return this != other;
+	}
+
+	let (_running, outermost) = Running::start();
+	let different = this != other;
+	if outermost {
+		check(this, other, !different);
+	}
+	different
+}
+
+/// Panics if the [`PartialEq`] implementation of `T` isn't reflexive or symmetric for these
+/// values, or if its `ne` doesn't agree with its `eq`. `equal` is the result of the comparison
+/// being checked.
+#[track_caller]
+#[allow(clippy::eq_op)] // Comparing a value with itself is the point of the reflexivity check
+fn check<T: PartialEq>(this: &T, other: &T, equal: bool) {
+	let name = type_name::<T>();
+	assert!(
+		this == this && other == other,
+		"the `Eq` implementation of `{name}` is not reflexive"
+	);
+	assert!(
+		(other == this) == equal,
+		"the `Eq` implementation of `{name}` is not symmetric"
+	);
+	assert!(
+		(this != other) != equal,
+		"the `ne` implementation of `{name}` doesn't agree with `eq`"
+	);
+}
@@ -0,0 +1,66 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Total equality for types that only implement [`PartialEq`].
+
+use core::ops::{Deref, DerefMut};
+
+/// Wrapper implementing [`Eq`] for types that only implement [`PartialEq`], such as floats.
+///
+/// Two wrapped values are equal if the inner values are equal, or if neither of them is equal to
+/// itself. For floats, this means that `NaN` is equal to `NaN` (whatever their bits), and still
+/// different from every other value. Note that for a type containing several floats, all the
+/// values containing a `NaN` are equal to each other.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::{DynEq, Total};
+///
+/// trait Shape: DynEq {}
+/// dyn_eq::eq_trait_object!(Shape);
+///
+/// #[derive(PartialEq)]
+/// struct Circle {
+///     radius: f64,
+/// }
+///
+/// impl Shape for Total<Circle> {}
+///
+/// let a: &dyn Shape = &Total(Circle { radius: 1.0 });
+/// let b: &dyn Shape = &Total(Circle { radius: 1.0 });
+/// let nan: &dyn Shape = &Total(Circle { radius: f64::NAN });
+///
+/// assert!(a == b);
+/// assert!(a != nan);
+/// assert!(nan == nan);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Total<T>(pub T);
+
+impl<T> Deref for Total<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Total<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T: PartialEq> PartialEq for Total<T> {
+	// Comparing a value with itself is how `NaN`-like values are detected
+	#[allow(clippy::eq_op)]
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0 || (self.0 != self.0 && other.0 != other.0)
+	}
+}
+
+impl<T: PartialEq> Eq for Total<T> {}
@@ -0,0 +1,99 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers to remove duplicated or specific trait objects from vectors.
+//!
+//! [`unique`] works on any [`PartialEq`] element, which includes `Box<dyn Trait>` once
+//! [`eq_trait_object`](crate::eq_trait_object) has been invoked for `Trait`. For the same reason,
+//! [`Vec::dedup`] can be used directly to remove consecutive equal trait objects. Like the helpers
+//! of the [`slice`](crate::slice) module, [`remove_item`] and [`retain_ne`] work on any pointer to
+//! a trait object, and the removed value doesn't need to be boxed.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::DynEq;
+
+/// Removes every element equal to a previous one, keeping the order of the remaining elements.
+///
+/// Unlike [`Vec::dedup`], the equal elements don't need to be consecutive.
+///
+/// Since trait objects can't be hashed, each element is compared with every kept element, which
+/// is quadratic in the length of the vector.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Command: DynEq {}
+/// dyn_eq::eq_trait_object!(Command);
+///
+/// impl Command for u8 {}
+/// impl Command for u16 {}
+///
+/// let mut commands: Vec<Box<dyn Command>> = vec![
+///     Box::new(1u8),
+///     Box::new(1u16),
+///     Box::new(1u8),
+///     Box::new(2u8),
+///     Box::new(1u16),
+/// ];
+/// dyn_eq::vec::unique(&mut commands);
+///
+/// let expected: Vec<Box<dyn Command>> = vec![Box::new(1u8), Box::new(1u16), Box::new(2u8)];
+/// assert!(commands == expected);
+/// ```
+pub fn unique<T: PartialEq>(vec: &mut Vec<T>) {
+	let mut kept = 0;
+	for i in 0..vec.len() {
+		if !vec[..kept].contains(&vec[i]) {
+			vec.swap(kept, i);
+			kept += 1;
+		}
+	}
+	vec.truncate(kept);
+}
+
+/// Removes the first element of type `T` equal to `value`, and returns it.
+///
+/// # Example
+///
+/// ```
+/// use dyn_eq::DynEq;
+///
+/// trait Command: DynEq {}
+/// dyn_eq::eq_trait_object!(Command);
+///
+/// impl Command for u8 {}
+/// impl Command for u16 {}
+///
+/// let mut commands: Vec<Box<dyn Command>> = vec![Box::new(1u8), Box::new(1u16), Box::new(1u8)];
+///
+/// assert!(dyn_eq::vec::remove_item(&mut commands, &1u8).is_some());
+/// assert!(dyn_eq::vec::remove_item(&mut commands, &2u8).is_none());
+///
+/// let expected: Vec<Box<dyn Command>> = vec![Box::new(1u16), Box::new(1u8)];
+/// assert!(commands == expected);
+/// ```
+pub fn remove_item<P, T>(vec: &mut Vec<P>, value: &T) -> Option<P>
+where
+	P: Deref,
+	P::Target: DynEq,
+	T: Eq + 'static,
+{
+	crate::slice::position_of(vec, value).map(|i| vec.remove(i))
+}
+
+/// Removes every element of type `T` equal to `value`, keeping the order of the remaining
+/// elements.
+pub fn retain_ne<P, T>(vec: &mut Vec<P>, value: &T)
+where
+	P: Deref,
+	P::Target: DynEq,
+	T: Eq + 'static,
+{
+	vec.retain(|element| !crate::eq(&**element, value));
+}
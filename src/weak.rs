@@ -0,0 +1,59 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers to compare weak pointers to trait objects.
+//!
+//! [`Weak`](alloc::rc::Weak) pointers don't implement [`PartialEq`], since the value they point to
+//! may have been dropped. These functions compare the values when both pointers can be upgraded,
+//! and otherwise consider two pointers equal only if they point to the same allocation. This means
+//! that a dead pointer is only equal to itself (or to its clones), and never to a live one.
+
+#[cfg(feature = "rc")]
+use alloc::rc;
+#[cfg(all(feature = "arc", target_has_atomic = "ptr"))]
+use alloc::sync;
+
+/// Compares two [`rc::Weak`] pointers.
+///
+/// # Example
+///
+/// ```
+/// use std::rc::{Rc, Weak};
+///
+/// use dyn_eq::DynEq;
+///
+/// trait Resource: DynEq {}
+/// dyn_eq::eq_trait_object!(Resource);
+///
+/// impl Resource for u8 {}
+///
+/// let a: Rc<dyn Resource> = Rc::new(1u8);
+/// let b: Rc<dyn Resource> = Rc::new(1u8);
+/// let (weak_a, weak_b) = (Rc::downgrade(&a), Rc::downgrade(&b));
+/// assert!(dyn_eq::weak::rc_eq(&weak_a, &weak_b));
+///
+/// drop(a);
+/// assert!(!dyn_eq::weak::rc_eq(&weak_a, &weak_b));
+/// assert!(dyn_eq::weak::rc_eq(&weak_a, &weak_a.clone()));
+/// ```
+#[cfg(feature = "rc")]
+pub fn rc_eq<T: ?Sized + PartialEq>(a: &rc::Weak<T>, b: &rc::Weak<T>) -> bool {
+	match (a.upgrade(), b.upgrade()) {
+		(Some(a), Some(b)) => *a == *b,
+		_ => rc::Weak::ptr_eq(a, b),
+	}
+}
+
+/// Compares two [`sync::Weak`] pointers.
+///
+/// Both pointers are upgraded before comparing, so the values can't be dropped by another thread
+/// during the comparison.
+#[cfg(all(feature = "arc", target_has_atomic = "ptr"))]
+pub fn arc_eq<T: ?Sized + PartialEq>(a: &sync::Weak<T>, b: &sync::Weak<T>) -> bool {
+	match (a.upgrade(), b.upgrade()) {
+		(Some(a), Some(b)) => *a == *b,
+		_ => sync::Weak::ptr_eq(a, b),
+	}
+}
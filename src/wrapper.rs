@@ -0,0 +1,219 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Comparable wrapper for trait objects of traits that don't have [`DynEq`](crate::DynEq) as a
+//! supertrait.
+
+use core::{
+	any::Any,
+	fmt::{self, Debug},
+	ops::{Deref, DerefMut},
+};
+
+use crate::Box;
+
+/// Wrap a value into a [`DynEqWrapper`].
+///
+/// `wrap_eq_trait_object!(dyn Trait, value)` creates a `DynEqWrapper<dyn Trait>` containing
+/// `value`, which must be [`Send`] and [`Sync`]. Other markers can be chosen with
+/// `wrap_eq_trait_object!(dyn Trait, value, markers = dyn Any)`, see [`DynEqWrapper`].
+#[macro_export]
+macro_rules! wrap_eq_trait_object {
+	($ty:ty, $value:expr,markers = $markers:ty $(,)?) => {
+		$crate::DynEqWrapper::<$ty, $markers>::new($value, |value| value, |value| value)
+	};
+	($ty:ty, $value:expr $(,)?) => {
+		$crate::DynEqWrapper::<$ty>::new($value, |value| value, |value| value)
+	};
+}
+
+/// A boxed trait object that can be compared, even if its trait doesn't have
+/// [`DynEq`](crate::DynEq) as a supertrait.
+///
+/// This is useful for traits of other crates, which can't be modified. The wrapper remembers how
+/// to compare the concrete value when it's created, and gives access to the trait object through
+/// [`Deref`]. It's usually created with [`wrap_eq_trait_object`](crate::wrap_eq_trait_object).
+///
+/// The wrapped value is stored as an `M`, which chooses the auto traits of the wrapper whatever
+/// the markers of `T`:
+/// - `dyn Any + Send + Sync` (the default): like [`AnyEq`](crate::AnyEq), the wrapped value must be
+///   [`Send`] and [`Sync`], and so is the wrapper.
+/// - `dyn Any + Send` or `dyn Any + Sync`: the wrapped value and the wrapper only have one of them.
+/// - `dyn Any`: the wrapped value can be neither, such as an [`Rc`](alloc::rc::Rc), and so is the
+///   wrapper.
+///
+/// # Example
+///
+/// ```
+/// use std::{any::Any, fmt::Display, rc::Rc};
+///
+/// use dyn_eq::DynEqWrapper;
+///
+/// let a: DynEqWrapper<dyn Display> = dyn_eq::wrap_eq_trait_object!(dyn Display, 5u8);
+/// let b: DynEqWrapper<dyn Display> = dyn_eq::wrap_eq_trait_object!(dyn Display, 5u16);
+///
+/// assert_eq!(a.to_string(), b.to_string());
+/// assert!(a != b);
+///
+/// let shared: DynEqWrapper<dyn Display, dyn Any> =
+///     dyn_eq::wrap_eq_trait_object!(dyn Display, Rc::new(5u8), markers = dyn Any);
+/// assert_eq!(shared.to_string(), "5");
+/// ```
+pub struct DynEqWrapper<T: ?Sized + 'static, M: ?Sized + WrapperMarkers = dyn Any + Send + Sync> {
+	/// The concrete value, and how to turn it into a trait object.
+	inner: Box<M::Erased<T>>,
+}
+
+impl<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> DynEqWrapper<T, M> {
+	/// Wrap `value`, using `view` and `view_mut` to turn it into a `T`.
+	///
+	/// Both functions are usually `|value| value`, which coerces the value to the trait object. For
+	/// this to work, `T` must be given explicitly (`DynEqWrapper::<dyn Trait>::new(..)`). The value
+	/// and both functions must implement the auto traits of `M`.
+	pub fn new<U, F, G>(value: U, view: F, view_mut: G) -> Self
+	where
+		U: Eq + 'static,
+		F: Fn(&U) -> &T + 'static,
+		G: Fn(&mut U) -> &mut T + 'static,
+		Holder<U, F, G>: Erase<T, M>,
+	{
+		Self {
+			inner: Holder {
+				value,
+				view,
+				view_mut,
+			}
+			.erase(),
+		}
+	}
+}
+
+impl<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> Deref for DynEqWrapper<T, M> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		self.inner.view()
+	}
+}
+
+impl<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> DerefMut for DynEqWrapper<T, M> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.inner.view_mut()
+	}
+}
+
+impl<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> PartialEq for DynEqWrapper<T, M> {
+	fn eq(&self, other: &Self) -> bool {
+		self.inner.eq_value(other.inner.value())
+	}
+}
+
+impl<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> Eq for DynEqWrapper<T, M> {}
+
+impl<T: ?Sized + Debug + 'static, M: ?Sized + WrapperMarkers> Debug for DynEqWrapper<T, M> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		(**self).fmt(f)
+	}
+}
+
+/// The trait objects that can be used as the markers of a [`DynEqWrapper`].
+///
+/// This trait is sealed, and implemented for `dyn Any` with any combination of [`Send`] and
+/// [`Sync`].
+pub trait WrapperMarkers: private::Sealed {
+	/// The wrapped value, with the auto traits of the markers.
+	#[doc(hidden)]
+	type Erased<T: ?Sized + 'static>: ?Sized + Erased<T>;
+}
+
+/// A wrapped value that implements the auto traits of `M`.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+	message = "the wrapped value doesn't implement the auto traits of `{M}`",
+	label = "the value or one of its view functions doesn't implement the auto traits of `{M}`"
+)]
+pub trait Erase<T: ?Sized + 'static, M: ?Sized + WrapperMarkers> {
+	/// Box the value with the auto traits of `M`.
+	fn erase(self) -> Box<M::Erased<T>>;
+}
+
+/// Implement [`WrapperMarkers`] and [`Erase`] for `dyn Any` with some auto traits.
+macro_rules! markers {
+	($($marker:ident),*) => {
+		impl private::Sealed for dyn Any $(+ $marker)* {}
+
+		impl WrapperMarkers for dyn Any $(+ $marker)* {
+			type Erased<T: ?Sized + 'static> = dyn Erased<T> $(+ $marker)*;
+		}
+
+		impl<T: ?Sized + 'static, H: Erased<T> $(+ $marker)* + 'static> Erase<T, dyn Any $(+ $marker)*> for H {
+			fn erase(self) -> Box<dyn Erased<T> $(+ $marker)*> {
+				Box::new(self)
+			}
+		}
+	};
+}
+
+markers!();
+markers!(Send);
+markers!(Sync);
+markers!(Send, Sync);
+
+/// Private module to seal the [`WrapperMarkers`] trait.
+mod private {
+	/// Sealing trait.
+	pub trait Sealed {}
+}
+
+/// A value of any type that can be turned into a `T`.
+#[doc(hidden)]
+pub trait Erased<T: ?Sized> {
+	/// Turn the value into a `&T`.
+	fn view(&self) -> &T;
+
+	/// Turn the value into a `&mut T`.
+	fn view_mut(&mut self) -> &mut T;
+
+	/// Upcast the value to a `&dyn Any`, which can then be passed to [`eq_value`](Erased::eq_value).
+	fn value(&self) -> &dyn Any;
+
+	/// Tests if the value is equal to `other`.
+	fn eq_value(&self, other: &dyn Any) -> bool;
+}
+
+/// A concrete value with the functions to turn it into a `T`.
+#[doc(hidden)]
+pub struct Holder<U, F, G> {
+	/// The value.
+	value: U,
+	/// Turns the value into a `&T`.
+	view: F,
+	/// Turns the value into a `&mut T`.
+	view_mut: G,
+}
+
+impl<T, U, F, G> Erased<T> for Holder<U, F, G>
+where
+	T: ?Sized,
+	U: Eq + 'static,
+	F: Fn(&U) -> &T,
+	G: Fn(&mut U) -> &mut T,
+{
+	fn view(&self) -> &T {
+		(self.view)(&self.value)
+	}
+
+	fn view_mut(&mut self) -> &mut T {
+		(self.view_mut)(&mut self.value)
+	}
+
+	fn value(&self) -> &dyn Any {
+		&self.value
+	}
+
+	fn eq_value(&self, other: &dyn Any) -> bool {
+		other.downcast_ref() == Some(&self.value)
+	}
+}
@@ -3,8 +3,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+
 use core::fmt::Debug;
-use dyn_eq::DynEq;
+
+use dyn_eq::{DynEq, DynEqExt};
+
+// Makes absolute paths to this crate available, for the path tests
+extern crate self as integration_test;
 
 trait MyTrait: DynEq + Debug {}
 dyn_eq::eq_trait_object!(MyTrait);
@@ -16,6 +22,19 @@ struct Container {
 	field: Box<dyn MyTrait>,
 }
 
+// This works too
+#[cfg(feature = "rc")]
+#[derive(PartialEq, Eq)]
+struct RcContainer {
+	field: std::rc::Rc<dyn MyTrait>,
+}
+
+#[cfg(feature = "arc")]
+#[derive(PartialEq, Eq)]
+struct ArcContainer {
+	field: std::sync::Arc<dyn MyTrait + Send + Sync>,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct A {
 	value: u32,
@@ -28,6 +47,18 @@ struct B {
 }
 impl MyTrait for B {}
 
+dyn_eq::dyn_eq_law_tests!(
+	my_trait_laws,
+	MyTrait,
+	[
+		A { value: 1 },
+		A { value: 1 },
+		A { value: 2 },
+		B { value: 1 },
+		B { value: 1 }
+	]
+);
+
 #[cfg(feature = "alloc")]
 mod with_box {
 	use super::*;
@@ -88,6 +119,353 @@ mod with_box {
 		assert!(!(a == b));
 		println!("{:?}", b);
 	}
+
+	#[test]
+	fn box_and_pointee_equal() {
+		let a1: Box<dyn MyTrait> = Box::new(A { value: 5 });
+		let a2: &dyn MyTrait = &A { value: 5 };
+		let b: &dyn MyTrait = &B { value: 5 };
+
+		assert!(*a1 == *a2);
+		assert!(*a2 == a1);
+		assert!(a1 != *b);
+		assert!(*b != a1);
+	}
+
+	#[derive(PartialEq, Eq, Debug)]
+	struct Handle(Box<dyn MyTrait>);
+
+	#[test]
+	fn newtype_comparison() {
+		let h1 = Handle(Box::new(A { value: 5 }));
+		let h2 = Handle(Box::new(A { value: 5 }));
+		let h3 = Handle(Box::new(B { value: 5 }));
+
+		assert_eq!(h1, h2);
+		assert_ne!(h1, h3);
+	}
+
+	#[test]
+	fn container_comparison() {
+		let c1 = Container {
+			field: Box::new(A { value: 5 }),
+		};
+		let c2 = Container {
+			field: Box::new(A { value: 5 }),
+		};
+		let c3 = Container {
+			field: Box::new(B { value: 5 }),
+		};
+
+		assert!(c1 == c2);
+		assert!(c1 != c3);
+	}
+}
+
+#[cfg(feature = "rc")]
+mod with_rc {
+	use std::rc::Rc;
+
+	use super::*;
+
+	#[test]
+	fn a_and_a_same_value_equal() {
+		let a1: Rc<dyn MyTrait> = Rc::new(A { value: 5 });
+		let a2: Rc<dyn MyTrait> = Rc::new(A { value: 5 });
+
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn a_and_a_different_value_not_equal() {
+		let a1: Rc<dyn MyTrait> = Rc::new(A { value: 5 });
+		let a2: Rc<dyn MyTrait> = Rc::new(A { value: 6 });
+
+		assert!(a1 != a2);
+	}
+
+	#[test]
+	fn a_and_b_same_value_not_equal() {
+		let a: Rc<dyn MyTrait> = Rc::new(A { value: 5 });
+		let b: Rc<dyn MyTrait> = Rc::new(B { value: 5 });
+
+		assert!(a != b);
+	}
+
+	#[test]
+	fn rc_and_pointee_equal() {
+		let a1: Rc<dyn MyTrait> = Rc::new(A { value: 5 });
+		let a2: &dyn MyTrait = &A { value: 5 };
+
+		assert!(*a1 == *a2);
+	}
+
+	#[test]
+	fn container_comparison() {
+		let c1 = RcContainer {
+			field: Rc::new(A { value: 5 }),
+		};
+		let c2 = RcContainer {
+			field: Rc::new(A { value: 5 }),
+		};
+		let c3 = RcContainer {
+			field: Rc::new(B { value: 5 }),
+		};
+
+		assert!(c1 == c2);
+		assert!(c1 != c3);
+	}
+}
+
+#[cfg(feature = "arc")]
+mod with_arc {
+	use std::sync::Arc;
+
+	use super::*;
+
+	#[test]
+	fn a_and_a_same_value_equal() {
+		let a1: Arc<dyn MyTrait + Send + Sync> = Arc::new(A { value: 5 });
+		let a2: Arc<dyn MyTrait + Send + Sync> = Arc::new(A { value: 5 });
+
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn a_and_a_different_value_not_equal() {
+		let a1: Arc<dyn MyTrait> = Arc::new(A { value: 5 });
+		let a2: Arc<dyn MyTrait> = Arc::new(A { value: 6 });
+
+		assert!(a1 != a2);
+	}
+
+	#[test]
+	fn a_and_b_same_value_not_equal() {
+		let a: Arc<dyn MyTrait> = Arc::new(A { value: 5 });
+		let b: Arc<dyn MyTrait> = Arc::new(B { value: 5 });
+
+		assert!(a != b);
+	}
+
+	#[test]
+	fn container_comparison() {
+		let c1 = ArcContainer {
+			field: Arc::new(A { value: 5 }),
+		};
+		let c2 = ArcContainer {
+			field: Arc::new(A { value: 5 }),
+		};
+		let c3 = ArcContainer {
+			field: Arc::new(B { value: 5 }),
+		};
+
+		assert!(c1 == c2);
+		assert!(c1 != c3);
+	}
+}
+
+#[cfg(feature = "alloc")]
+mod with_pin {
+	use std::pin::Pin;
+
+	use super::*;
+
+	#[derive(PartialEq, Eq)]
+	struct PinContainer {
+		field: Pin<Box<dyn MyTrait>>,
+		send: Pin<Box<dyn MyTrait + Send>>,
+	}
+
+	#[test]
+	fn a_and_a_same_value_equal() {
+		let a1: Pin<Box<dyn MyTrait>> = Box::pin(A { value: 5 });
+		let a2: Pin<Box<dyn MyTrait>> = Box::pin(A { value: 5 });
+
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn a_and_b_same_value_not_equal() {
+		let a: Pin<Box<dyn MyTrait>> = Box::pin(A { value: 5 });
+		let b: Pin<Box<dyn MyTrait>> = Box::pin(B { value: 5 });
+
+		assert!(a != b);
+	}
+
+	#[test]
+	fn container_comparison() {
+		let c1 = PinContainer {
+			field: Box::pin(A { value: 5 }),
+			send: Box::pin(B { value: 5 }),
+		};
+		let c2 = PinContainer {
+			field: Box::pin(A { value: 5 }),
+			send: Box::pin(B { value: 5 }),
+		};
+		let c3 = PinContainer {
+			field: Box::pin(A { value: 5 }),
+			send: Box::pin(A { value: 5 }),
+		};
+
+		assert!(c1 == c2);
+		assert!(c1 != c3);
+	}
+}
+
+mod downcasting {
+	use super::*;
+
+	#[test]
+	fn as_any_mut() {
+		let mut a = A { value: 5 };
+		let a_dyn: &mut dyn MyTrait = &mut a;
+
+		a_dyn.as_any_mut().downcast_mut::<A>().unwrap().value = 6;
+		assert!(a_dyn.as_any_mut().downcast_mut::<B>().is_none());
+		assert_eq!(a.value, 6);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn as_any_mut_through_box() {
+		let mut a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		(*a).as_any_mut().downcast_mut::<A>().unwrap().value = 6;
+		assert!(a == Box::new(A { value: 6 }) as Box<dyn MyTrait>);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn into_any() {
+		let a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+		let b: Box<dyn MyTrait> = Box::new(B { value: 5 });
+
+		assert_eq!(a.into_any().downcast::<A>().unwrap().value, 5);
+		assert!(b.into_any().downcast::<A>().is_err());
+	}
+
+	#[test]
+	fn free_functions() {
+		let mut a = A { value: 5 };
+		let mut a_dyn: &mut dyn MyTrait = &mut a;
+
+		assert!(dyn_eq::is::<A>(&a_dyn));
+		assert!(!dyn_eq::is::<B>(&a_dyn));
+		assert_eq!(dyn_eq::downcast_ref::<A>(&a_dyn).unwrap().value, 5);
+		assert!(dyn_eq::downcast_ref::<B>(&a_dyn).is_none());
+		dyn_eq::downcast_mut::<A>(&mut a_dyn).unwrap().value = 6;
+		assert!(dyn_eq::downcast_mut::<B>(&mut a_dyn).is_none());
+		assert_eq!(a.value, 6);
+	}
+
+	#[test]
+	fn extension_methods() {
+		let mut a = A { value: 5 };
+
+		let a_dyn: &dyn MyTrait = &a;
+		assert!(a_dyn.is::<A>());
+		assert!(!a_dyn.is::<B>());
+		assert_eq!(a_dyn.downcast_ref::<A>().unwrap().value, 5);
+		assert!(a_dyn.downcast_ref::<B>().is_none());
+
+		let a_dyn: &mut dyn MyTrait = &mut a;
+		a_dyn.downcast_mut::<A>().unwrap().value = 6;
+		assert!(a_dyn.downcast_mut::<B>().is_none());
+		assert_eq!(a.value, 6);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn extension_methods_through_box() {
+		let mut a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		assert!(a.is::<A>());
+		assert!(!a.is::<B>());
+		assert_eq!(a.downcast_ref::<A>().unwrap().value, 5);
+		assert!(a.downcast_ref::<B>().is_none());
+		a.downcast_mut::<A>().unwrap().value = 6;
+		assert!(a.downcast_mut::<B>().is_none());
+		assert_eq!(a.downcast_ref::<A>().unwrap().value, 6);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn free_functions_through_box() {
+		let mut a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		assert!(dyn_eq::is::<A>(&a));
+		assert!(!dyn_eq::is::<B>(&a));
+		dyn_eq::downcast_mut::<A>(&mut a).unwrap().value = 6;
+		assert_eq!(dyn_eq::downcast_ref::<A>(&a).unwrap().value, 6);
+		assert!(dyn_eq::downcast_ref::<B>(&a).is_none());
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn free_downcast() {
+		let a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		let a = dyn_eq::downcast::<B, _>(a).unwrap_err();
+		assert!(dyn_eq::downcast_ref::<A>(&a).is_some());
+		assert_eq!(dyn_eq::downcast::<A, _>(a).unwrap().value, 5);
+	}
+}
+
+mod eq_concrete {
+	use super::*;
+
+	#[test]
+	fn same_type_same_value() {
+		let a: &dyn MyTrait = &A { value: 5 };
+
+		assert!(a.eq_concrete(&A { value: 5 }));
+	}
+
+	#[test]
+	fn same_type_different_value() {
+		let a: &dyn MyTrait = &A { value: 5 };
+
+		assert!(!a.eq_concrete(&A { value: 6 }));
+	}
+
+	#[test]
+	fn different_type_same_value() {
+		let a: &dyn MyTrait = &A { value: 5 };
+
+		assert!(!a.eq_concrete(&B { value: 5 }));
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn through_box() {
+		let a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		assert!(a.eq_concrete(&A { value: 5 }));
+		assert!(!a.eq_concrete(&B { value: 5 }));
+	}
+}
+
+mod type_info {
+	use core::any::TypeId;
+
+	use super::*;
+
+	#[test]
+	fn dyn_type_id() {
+		let a: &dyn MyTrait = &A { value: 5 };
+		let b: &dyn MyTrait = &B { value: 5 };
+
+		assert_eq!(a.dyn_type_id(), TypeId::of::<A>());
+		assert_eq!(b.dyn_type_id(), TypeId::of::<B>());
+	}
+
+	#[cfg(feature = "type-name")]
+	#[test]
+	fn dyn_type_name() {
+		let a: &dyn MyTrait = &A { value: 5 };
+
+		assert_eq!(a.dyn_type_name(), core::any::type_name::<A>());
+	}
 }
 
 mod with_ref {
@@ -141,3 +519,2516 @@ mod with_ref {
 		assert!(a != b);
 	}
 }
+
+mod cross_markers {
+	use super::*;
+
+	#[test]
+	fn plain_and_send_sync_same_value_equal() {
+		let a1: &dyn MyTrait = &A { value: 5 };
+		let a2: &(dyn MyTrait + Send + Sync) = &A { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a2 == a1);
+	}
+
+	#[test]
+	fn send_and_sync_different_type_not_equal() {
+		let a: &(dyn MyTrait + Send) = &A { value: 5 };
+		let b: &(dyn MyTrait + Sync) = &B { value: 5 };
+
+		assert!(a != b);
+		assert!(b != a);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed_plain_and_send_same_value_equal() {
+		let a1: Box<dyn MyTrait> = Box::new(A { value: 5 });
+		let a2: Box<dyn MyTrait + Send> = Box::new(A { value: 5 });
+
+		assert!(a1 == a2);
+		assert!(a2 == a1);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed_sync_and_send_sync_different_value_not_equal() {
+		let a1: Box<dyn MyTrait + Sync> = Box::new(A { value: 5 });
+		let a2: Box<dyn MyTrait + Send + Sync> = Box::new(A { value: 6 });
+
+		assert!(a1 != a2);
+		assert!(a2 != a1);
+	}
+}
+
+mod markers {
+	use super::*;
+
+	trait OnlySend: DynEq {}
+	dyn_eq::eq_trait_object!(OnlySend; markers(Send));
+
+	impl OnlySend for A {}
+
+	// Doesn't conflict with the generated impls.
+	impl PartialEq for dyn OnlySend + Sync {
+		fn eq(&self, _: &Self) -> bool {
+			false
+		}
+	}
+
+	trait Multiple: DynEq {}
+	dyn_eq::eq_trait_object!(Multiple; markers(Send, Send + Sync,));
+
+	impl Multiple for A {}
+
+	trait OtherAutoTraits: DynEq {}
+	dyn_eq::eq_trait_object!(OtherAutoTraits; markers(
+		Unpin,
+		Send + ::core::panic::UnwindSafe,
+		Send + Sync + std::panic::RefUnwindSafe,
+	));
+
+	impl OtherAutoTraits for A {}
+	impl OtherAutoTraits for B {}
+
+	trait NoMarkers: DynEq {}
+	dyn_eq::eq_trait_object!(NoMarkers; markers());
+
+	impl NoMarkers for A {}
+
+	#[test]
+	fn generated_combinations() {
+		let a1: &(dyn OnlySend + Send) = &A { value: 5 };
+		let a2: &(dyn OnlySend + Send) = &A { value: 5 };
+		let a3: &dyn OnlySend = &A { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a1 == a3);
+	}
+
+	#[test]
+	fn manual_combination() {
+		let a1: &(dyn OnlySend + Sync) = &A { value: 5 };
+		let a2: &(dyn OnlySend + Sync) = &A { value: 5 };
+
+		assert!(a1 != a2);
+	}
+
+	#[test]
+	fn multiple_combinations() {
+		let a1: &(dyn Multiple + Send) = &A { value: 5 };
+		let a2: &(dyn Multiple + Send + Sync) = &A { value: 5 };
+		let a3: &dyn Multiple = &A { value: 6 };
+
+		assert!(a1 == a2);
+		assert!(a2 != a3);
+	}
+
+	#[test]
+	fn other_auto_traits() {
+		let a1: &(dyn OtherAutoTraits + Unpin) = &A { value: 5 };
+		let a2: &(dyn OtherAutoTraits + Send + core::panic::UnwindSafe) = &A { value: 5 };
+		let b: &(dyn OtherAutoTraits + Send + Sync + core::panic::RefUnwindSafe) = &B { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a2 != b);
+	}
+
+	#[test]
+	fn no_markers() {
+		let a1: &dyn NoMarkers = &A { value: 5 };
+		let a2: &dyn NoMarkers = &A { value: 5 };
+
+		assert!(a1 == a2);
+	}
+}
+
+mod multiple_traits {
+	use super::*;
+
+	trait First: DynEq {}
+	trait Second<X, Y>: DynEq {}
+	trait Third<T>: DynEq
+	where
+		T: Clone,
+	{
+	}
+	dyn_eq::eq_trait_object!(
+		First,
+		<X: 'static, Y: 'static> Second<X, Y>,
+		<T> Third<T> where T: Clone + 'static
+	);
+
+	trait Fourth: DynEq {}
+	trait Fifth: DynEq {}
+	dyn_eq::eq_trait_object!(Fourth, Fifth,; markers(Send));
+
+	impl First for A {}
+	impl Second<u8, u16> for A {}
+	impl Third<u8> for A {}
+	impl Fourth for A {}
+	impl Fifth for A {}
+
+	#[test]
+	fn every_trait_implemented() {
+		let a = A { value: 5 };
+
+		assert!(&a as &dyn First == &a as &dyn First);
+		assert!(&a as &dyn Second<u8, u16> == &a as &dyn Second<u8, u16>);
+		assert!(&a as &dyn Third<u8> == &a as &dyn Third<u8>);
+		assert!(&a as &(dyn Fourth + Send) == &a as &dyn Fourth);
+		assert!(&a as &(dyn Fifth + Send) == &a as &dyn Fifth);
+	}
+}
+
+mod indirect_supertrait {
+	use super::*;
+
+	trait Base: DynEq {}
+	dyn_eq::eq_trait_object!(Base);
+
+	trait Node: Base {}
+	dyn_eq::eq_trait_object!(Node);
+
+	trait Leaf<T>: Node {}
+	dyn_eq::eq_trait_object!(<T> Leaf<T> where T: 'static);
+
+	impl Base for A {}
+	impl Base for B {}
+	impl Node for A {}
+	impl Node for B {}
+	impl Leaf<u8> for A {}
+
+	#[test]
+	fn comparisons() {
+		let a1: &dyn Node = &A { value: 5 };
+		let a2: &(dyn Node + Send) = &A { value: 5 };
+		let b: &dyn Node = &B { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a1 != b);
+		assert!(&A { value: 5 } as &dyn Leaf<u8> == &A { value: 5 } as &dyn Leaf<u8>);
+	}
+
+	#[test]
+	fn inherited_methods() {
+		let a: &dyn Node = &A { value: 5 };
+
+		assert!(a.dyn_eq(A { value: 5 }.as_any()));
+		assert!(a.eq_concrete(&A { value: 5 }));
+		assert!(a.is::<A>());
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let a1: Box<dyn Node> = Box::new(A { value: 5 });
+		let a2: Box<dyn Node> = Box::new(A { value: 5 });
+		let b: Box<dyn Node> = Box::new(B { value: 5 });
+
+		assert!(a1 == a2);
+		assert!(a1 != b);
+		assert!(dyn_eq::eq(&*a1, &*a2));
+	}
+}
+
+mod attributes {
+	use super::*;
+
+	#[cfg(any())]
+	trait Disabled: DynEq {}
+	trait Enabled: DynEq {}
+
+	dyn_eq::eq_trait_object!(
+		#[cfg(any())]
+		Disabled,
+		#[cfg(all())]
+		#[doc(hidden)]
+		#[deny(unused_qualifications)]
+		Enabled
+	);
+
+	impl Enabled for A {}
+
+	#[test]
+	fn enabled_trait_implemented() {
+		let a1: &dyn Enabled = &A { value: 5 };
+		let a2: &dyn Enabled = &A { value: 5 };
+
+		assert!(a1 == a2);
+	}
+}
+
+mod unsafe_traits {
+	use super::*;
+
+	/// # Safety
+	///
+	/// Only for tests.
+	unsafe trait Raw: DynEq {}
+	/// # Safety
+	///
+	/// Only for tests.
+	unsafe trait RawChannel<T>: DynEq {}
+	dyn_eq::eq_trait_object!(unsafe Raw, unsafe <T> RawChannel<T> where T: 'static);
+
+	unsafe impl Raw for A {}
+	unsafe impl Raw for B {}
+	unsafe impl RawChannel<u8> for A {}
+
+	#[test]
+	fn comparisons() {
+		let a: &dyn Raw = &A { value: 1 };
+		let b: &(dyn Raw + Send) = &B { value: 1 };
+		let channel: &dyn RawChannel<u8> = &A { value: 1 };
+
+		assert!(a == a);
+		assert!(a != b);
+		assert!(channel == channel);
+	}
+}
+
+mod const_generics {
+	use super::*;
+
+	trait Fixed<const N: usize>: DynEq {}
+	dyn_eq::eq_trait_object!(<const N: usize> Fixed<N>);
+
+	trait WithDefault<const N: usize = 3, const M: bool = { 1 > 2 }>: DynEq {}
+	dyn_eq::eq_trait_object!(<const N: usize = 3, const M: bool = { 1 > 2 }> WithDefault<N, M>);
+
+	impl Fixed<3> for A {}
+	impl Fixed<3> for B {}
+	impl WithDefault for A {}
+	impl WithDefault<4, true> for A {}
+
+	#[test]
+	fn const_parameter() {
+		let a: &dyn Fixed<3> = &A { value: 5 };
+		let b: &dyn Fixed<3> = &B { value: 5 };
+
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn const_parameter_with_default() {
+		let a1: &dyn WithDefault = &A { value: 5 };
+		let a2: &dyn WithDefault<3, false> = &A { value: 5 };
+		let a3: &dyn WithDefault<4, true> = &A { value: 6 };
+		let a4: &dyn WithDefault<4, true> = &A { value: 6 };
+
+		assert!(a1 == a2);
+		assert!(a3 == a4);
+	}
+}
+
+mod type_defaults {
+	use super::*;
+
+	trait Store<K, V = Vec<u8>>: DynEq {}
+	dyn_eq::eq_trait_object!(<K: 'static, V: 'static = Vec<u8>> Store<K, V>);
+
+	trait Pair<K, V = K>: DynEq {}
+	dyn_eq::eq_trait_object!(<K: 'static, V: 'static = K> Pair<K, V>);
+
+	trait Nested<V = Vec<Vec<u8>>, K = u8>: DynEq {}
+	dyn_eq::eq_trait_object!(<V: 'static = Vec<Vec<u8>>, K: 'static = u8> Nested<V, K>);
+
+	impl Store<u8> for A {}
+	impl Store<u8> for B {}
+	impl Pair<u8> for A {}
+	impl Nested for A {}
+
+	#[test]
+	fn type_parameter_with_default() {
+		let a: &dyn Store<u8> = &A { value: 5 };
+		let b: &dyn Store<u8, Vec<u8>> = &B { value: 5 };
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn default_referencing_parameter() {
+		let a1: &dyn Pair<u8> = &A { value: 5 };
+		let a2: &dyn Pair<u8, u8> = &A { value: 5 };
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn nested_default() {
+		let a1: &dyn Nested = &A { value: 5 };
+		let a2: &dyn Nested<Vec<Vec<u8>>, u8> = &A { value: 5 };
+		assert!(a1 == a2);
+	}
+}
+
+mod nested_generics {
+	use super::*;
+
+	trait Codec<T: Into<Vec<u8>>>: DynEq {}
+	trait Table<K, V>: DynEq {}
+	dyn_eq::eq_trait_object!(
+		<T: Into<Vec<u8>> + 'static> Codec<T>,
+		<K: AsRef<Vec<Vec<u8>>>, V: From<Vec<K>>> Table<K, V> where K: 'static, V: 'static
+	);
+
+	trait Matrix<T>: DynEq {}
+	dyn_eq::eq_trait_object!(Matrix<Vec<Vec<u8>>>);
+
+	impl Codec<String> for A {}
+	impl Table<Vec<Vec<u8>>, Vec<Vec<Vec<u8>>>> for A {}
+	impl Matrix<Vec<Vec<u8>>> for A {}
+	impl Matrix<Vec<Vec<u8>>> for B {}
+
+	#[test]
+	fn bounds() {
+		let a: &dyn Codec<String> = &A { value: 1 };
+		let b: &dyn Table<Vec<Vec<u8>>, Vec<Vec<Vec<u8>>>> = &A { value: 1 };
+		assert!(a == a);
+		assert!(b == b);
+	}
+
+	#[test]
+	fn nested_arguments() {
+		let a: &dyn Matrix<Vec<Vec<u8>>> = &A { value: 1 };
+		let b: &dyn Matrix<Vec<Vec<u8>>> = &B { value: 1 };
+		assert!(a != b);
+	}
+}
+
+mod higher_ranked {
+	use super::*;
+
+	trait Handler<F>: DynEq
+	where
+		F: for<'a> Fn(&'a str) -> bool,
+	{
+	}
+	dyn_eq::eq_trait_object!(<F> Handler<F> where F: for<'a> Fn(&'a str) -> bool + 'static);
+
+	trait InGenerics<F: for<'a> Fn(&'a str) -> bool>: DynEq {}
+	dyn_eq::eq_trait_object!(<F: for<'a> Fn(&'a str) -> bool + 'static> InGenerics<F>);
+
+	type Callback = fn(&str) -> bool;
+
+	impl Handler<Callback> for A {}
+	impl Handler<Callback> for B {}
+	impl InGenerics<Callback> for A {}
+
+	#[test]
+	fn where_clause() {
+		let a: &dyn Handler<Callback> = &A { value: 5 };
+		let b: &dyn Handler<Callback> = &B { value: 5 };
+
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn generics_list() {
+		let a1: &dyn InGenerics<Callback> = &A { value: 5 };
+		let a2: &dyn InGenerics<Callback> = &A { value: 5 };
+		let a3: &dyn InGenerics<Callback> = &A { value: 6 };
+
+		assert!(a1 == a2);
+		assert!(a1 != a3);
+	}
+}
+
+mod associated_types {
+	use super::*;
+
+	trait Producer: DynEq {
+		type Item;
+	}
+	dyn_eq::eq_trait_object!(<T> Producer<Item = T> where T: 'static);
+
+	trait Converter<I>: DynEq {
+		type Output;
+	}
+	dyn_eq::eq_trait_object!(<I, O> Converter<I, Output = O> where I: 'static, O: 'static);
+
+	impl Producer for A {
+		type Item = u8;
+	}
+	impl Producer for B {
+		type Item = u8;
+	}
+	impl Converter<u8> for A {
+		type Output = u16;
+	}
+
+	#[test]
+	fn binding() {
+		let a: &dyn Producer<Item = u8> = &A { value: 5 };
+		let b: &dyn Producer<Item = u8> = &B { value: 5 };
+
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn binding_with_generics() {
+		let a1: &dyn Converter<u8, Output = u16> = &A { value: 5 };
+		let a2: &dyn Converter<u8, Output = u16> = &A { value: 5 };
+
+		assert!(a1 == a2);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let a1: Box<dyn Producer<Item = u8>> = Box::new(A { value: 5 });
+		let a2: Box<dyn Producer<Item = u8>> = Box::new(A { value: 6 });
+
+		assert!(a1 != a2);
+	}
+}
+
+mod paths {
+	use super::*;
+
+	pub mod model {
+		use super::*;
+
+		pub trait Node: DynEq {}
+		pub trait Generic<T>: DynEq {}
+
+		impl Node for A {}
+		impl Node for B {}
+		impl Generic<u8> for A {}
+
+		pub mod nested {
+			use super::*;
+
+			pub trait Leaf: DynEq {}
+			impl Leaf for A {}
+
+			// Relative to the parent module
+			dyn_eq::eq_trait_object!(super::super::registration::Local);
+		}
+	}
+
+	mod registration {
+		use super::*;
+
+		pub trait Local: DynEq {}
+		impl Local for A {}
+
+		dyn_eq::eq_trait_object!(crate::paths::model::Node);
+		dyn_eq::eq_trait_object!(<T> super::model::Generic<T> where T: 'static);
+		dyn_eq::eq_trait_object!(::integration_test::paths::model::nested::Leaf);
+	}
+
+	pub trait Here: DynEq {}
+	impl Here for A {}
+	dyn_eq::eq_trait_object!(self::Here);
+
+	#[test]
+	fn crate_path() {
+		let a: &dyn model::Node = &A { value: 5 };
+		let b: &dyn model::Node = &B { value: 5 };
+
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn super_path() {
+		let a1: &dyn model::Generic<u8> = &A { value: 5 };
+		let a2: &dyn model::Generic<u8> = &A { value: 6 };
+		let b1: &dyn registration::Local = &A { value: 5 };
+		let b2: &dyn registration::Local = &A { value: 5 };
+
+		assert!(a1 != a2);
+		assert!(b1 == b2);
+	}
+
+	#[test]
+	fn absolute_path() {
+		let a1: &dyn model::nested::Leaf = &A { value: 5 };
+		let a2: &dyn model::nested::Leaf = &A { value: 5 };
+
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn self_path() {
+		let a1: &dyn Here = &A { value: 5 };
+		let a2: &dyn Here = &A { value: 6 };
+
+		assert!(a1 != a2);
+	}
+}
+
+mod lifetimes {
+	use super::*;
+
+	trait View<'ctx>: DynEq {
+		fn label(&self) -> &'ctx str;
+	}
+	dyn_eq::eq_trait_object!(<'ctx> View<'ctx> where 'ctx: 'static);
+
+	trait Mixed<'a, T>: DynEq {}
+	dyn_eq::eq_trait_object!(<'a, T: 'a> Mixed<'a, T> where 'a: 'static, T: 'static);
+
+	impl View<'static> for A {
+		fn label(&self) -> &'static str {
+			"a"
+		}
+	}
+	impl View<'static> for B {
+		fn label(&self) -> &'static str {
+			"b"
+		}
+	}
+	impl Mixed<'static, u8> for A {}
+
+	#[test]
+	fn lifetime_parameter() {
+		let a: &dyn View = &A { value: 5 };
+		let b: &dyn View = &B { value: 5 };
+
+		assert_eq!(a.label(), "a");
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	fn lifetime_and_type_parameters() {
+		let a1: &dyn Mixed<u8> = &A { value: 5 };
+		let a2: &dyn Mixed<u8> = &A { value: 5 };
+
+		assert!(a1 == a2);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		#[derive(PartialEq, Eq)]
+		struct Widget {
+			view: Box<dyn View<'static>>,
+		}
+
+		let a = Widget {
+			view: Box::new(A { value: 5 }),
+		};
+		let b = Widget {
+			view: Box::new(B { value: 5 }),
+		};
+
+		assert!(a != b);
+	}
+}
+
+mod static_only {
+	use super::*;
+
+	trait Setting: DynEq {}
+	dyn_eq::eq_trait_object!(Setting; static_only);
+
+	trait Generic<T>: DynEq {}
+	dyn_eq::eq_trait_object!(<T> Generic<T> where T: 'static; static_only, ptr_eq);
+
+	impl Setting for A {}
+	impl Setting for B {}
+	impl Generic<u8> for A {}
+
+	#[test]
+	fn references() {
+		let a1: &dyn Setting = &A { value: 5 };
+		let a2: &(dyn Setting + Send) = &A { value: 5 };
+		let b: &(dyn Setting + Sync) = &B { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a2 == a1);
+		assert!(a1 != b);
+	}
+
+	#[test]
+	fn with_options() {
+		let a1: &dyn Generic<u8> = &A { value: 5 };
+		let a2: &dyn Generic<u8> = &A { value: 5 };
+
+		assert!(a1 == a1);
+		assert!(a1 == a2);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let a1: Box<dyn Setting> = Box::new(A { value: 5 });
+		let a2: Box<dyn Setting + Send + Sync> = Box::new(A { value: 5 });
+
+		assert!(a1 == a2);
+		assert!(*a1 == *a2);
+	}
+}
+
+#[cfg(feature = "alloc")]
+mod mixed_pointers {
+	use super::*;
+
+	trait Resource: DynEq {}
+	dyn_eq::eq_trait_object!(Resource; mixed_pointers, markers(Send + Sync));
+
+	impl Resource for A {}
+	impl Resource for B {}
+
+	#[test]
+	fn boxes_and_references() {
+		let a: Box<dyn Resource> = Box::new(A { value: 1 });
+		let b: &dyn Resource = &A { value: 1 };
+		let c: &dyn Resource = &B { value: 1 };
+
+		assert!(a == b);
+		assert!(b == a);
+		assert!(a != c);
+		assert!(c != a);
+	}
+
+	#[cfg(all(feature = "rc", feature = "arc"))]
+	#[test]
+	fn shared() {
+		use std::{rc::Rc, sync::Arc};
+
+		let a: Box<dyn Resource + Send + Sync> = Box::new(A { value: 1 });
+		let b: Rc<dyn Resource + Send + Sync> = Rc::new(A { value: 1 });
+		let c: Arc<dyn Resource + Send + Sync> = Arc::new(A { value: 1 });
+		let d: &(dyn Resource + Send + Sync) = &B { value: 1 };
+
+		assert!(a == b);
+		assert!(b == a);
+		assert!(a == c);
+		assert!(c == a);
+		assert!(b != d);
+		assert!(d != b);
+		assert!(c != d);
+		assert!(d != c);
+	}
+
+	#[test]
+	fn derive() {
+		#[derive(PartialEq, Eq)]
+		struct Holder {
+			resource: Box<dyn Resource>,
+		}
+
+		let a = Holder {
+			resource: Box::new(A { value: 1 }),
+		};
+		let b = Holder {
+			resource: Box::new(B { value: 1 }),
+		};
+		assert!(a != b);
+	}
+}
+
+mod prelude {
+	use dyn_eq::prelude::*;
+
+	use super::{A, B};
+
+	trait Prelude: DynEq {}
+	eq_trait_object!(Prelude);
+
+	impl Prelude for A {}
+	impl Prelude for B {}
+
+	#[test]
+	fn glob_import() {
+		let a: &dyn Prelude = &A { value: 5 };
+		let b: &dyn Prelude = &B { value: 5 };
+
+		assert!(a != b);
+		assert!(a.eq_concrete(&A { value: 5 }));
+	}
+}
+
+mod free_functions {
+	use super::*;
+
+	#[test]
+	fn erased_values() {
+		let values: [&dyn DynEq; 4] = [
+			&A { value: 5 },
+			&A { value: 5 },
+			&A { value: 6 },
+			&B { value: 5 },
+		];
+
+		assert!(dyn_eq::eq(values[0], values[1]));
+		assert!(dyn_eq::ne(values[0], values[2]));
+		assert!(dyn_eq::ne(values[0], values[3]));
+	}
+
+	#[test]
+	fn mixed_with_concrete() {
+		let a: &dyn MyTrait = &A { value: 5 };
+
+		assert!(dyn_eq::eq(a, &A { value: 5 }));
+		assert!(dyn_eq::ne(&B { value: 5 }, a));
+	}
+
+	#[test]
+	fn by_key() {
+		let a: &dyn MyTrait = &A { value: 5 };
+		let b: &dyn MyTrait = &B { value: 5 };
+		let c: &dyn MyTrait = &A { value: 6 };
+
+		// Compare the values by their `Debug` representation without the type name
+		let key = |value: &dyn MyTrait| format!("{value:?}").split_off(1);
+		assert!(dyn_eq::eq_by_key(a, b, key));
+		assert!(!dyn_eq::eq_by_key(a, c, key));
+		assert!(dyn_eq::eq_by_key(a, c, |value| value.is::<A>()));
+	}
+}
+
+mod dyn_dyn_eq {
+	use super::*;
+
+	#[test]
+	fn references() {
+		let a1: &dyn DynEq = &A { value: 5 };
+		let a2: &dyn DynEq = &A { value: 5 };
+		let b: &dyn DynEq = &B { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(a1 != b);
+	}
+
+	#[test]
+	fn markers() {
+		let a1: &(dyn DynEq + Send) = &A { value: 5 };
+		let a2: &(dyn DynEq + Send) = &A { value: 5 };
+		let b: &(dyn DynEq + Send + Sync) = &B { value: 5 };
+
+		assert!(a1 == a2);
+		assert!(b == b);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		#[derive(PartialEq, Eq)]
+		struct AnyContainer {
+			field: Box<dyn DynEq>,
+		}
+
+		let a = AnyContainer {
+			field: Box::new(A { value: 5 }),
+		};
+		let b = AnyContainer {
+			field: Box::new(5u8),
+		};
+
+		assert!(a == a);
+		assert!(a != b);
+		assert!(a.field == Box::new(A { value: 5 }) as Box<dyn DynEq>);
+	}
+}
+
+// The strict checks do additional comparisons, which would be counted
+#[cfg(not(feature = "strict-checks"))]
+mod ptr_eq {
+	use core::sync::atomic::{AtomicUsize, Ordering};
+
+	use super::*;
+
+	static COMPARISONS: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(Debug)]
+	struct Counted(u8);
+
+	impl PartialEq for Counted {
+		fn eq(&self, other: &Self) -> bool {
+			COMPARISONS.fetch_add(1, Ordering::Relaxed);
+			self.0 == other.0
+		}
+	}
+	impl Eq for Counted {}
+
+	#[derive(Debug)]
+	struct Zst;
+
+	impl PartialEq for Zst {
+		fn eq(&self, _: &Self) -> bool {
+			COMPARISONS.fetch_add(1, Ordering::Relaxed);
+			true
+		}
+	}
+	impl Eq for Zst {}
+
+	#[derive(PartialEq, Eq, Debug)]
+	struct Wrapper {
+		inner: Counted,
+	}
+
+	trait Shared: DynEq {}
+	dyn_eq::eq_trait_object!(Shared; ptr_eq);
+
+	impl Shared for Counted {}
+	impl Shared for Zst {}
+	impl Shared for Wrapper {}
+
+	// The counter is shared, so everything is checked in a single test
+	#[test]
+	fn skips_eq() {
+		let value = Counted(5);
+		let a: &dyn Shared = &value;
+		let b: &dyn Shared = &value;
+		let c: &dyn Shared = &Counted(5);
+
+		assert!(a == b);
+		assert_eq!(COMPARISONS.load(Ordering::Relaxed), 0);
+		assert!(a == c);
+		assert_eq!(COMPARISONS.load(Ordering::Relaxed), 1);
+
+		let a: &(dyn Shared + Send) = &value;
+		assert!(a == a);
+		assert_eq!(COMPARISONS.load(Ordering::Relaxed), 1);
+
+		// Zero-sized values are never compared
+		let zst: &dyn Shared = &Zst;
+		let other_zst: &dyn Shared = &Zst;
+		assert!(zst == other_zst);
+		assert_eq!(COMPARISONS.load(Ordering::Relaxed), 1);
+
+		// Same address, but different types
+		let wrapper = Wrapper { inner: Counted(5) };
+		let outer: &dyn Shared = &wrapper;
+		let inner: &dyn Shared = &wrapper.inner;
+		assert!(outer != inner);
+	}
+}
+
+#[cfg(feature = "collections")]
+mod vec {
+	use super::*;
+
+	fn commands() -> Vec<Box<dyn MyTrait>> {
+		vec![
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(B { value: 1 }),
+		]
+	}
+
+	#[test]
+	fn dedup() {
+		let mut commands = commands();
+		commands.dedup();
+
+		let expected: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(A { value: 1 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(B { value: 1 }),
+		];
+		assert!(commands == expected);
+	}
+
+	#[test]
+	fn unique() {
+		let mut commands = commands();
+		dyn_eq::vec::unique(&mut commands);
+
+		let expected: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(A { value: 1 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 2 }),
+		];
+		assert!(commands == expected);
+	}
+
+	#[test]
+	fn remove_item() {
+		let mut commands = commands();
+
+		let removed = dyn_eq::vec::remove_item(&mut commands, &B { value: 1 });
+		assert!(removed.is_some_and(|removed| (*removed).eq_concrete(&B { value: 1 })));
+		assert!(dyn_eq::vec::remove_item(&mut commands, &B { value: 2 }).is_none());
+
+		let expected: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(B { value: 1 }),
+		];
+		assert!(commands == expected);
+	}
+
+	#[test]
+	fn retain_ne() {
+		let mut commands = commands();
+		dyn_eq::vec::retain_ne(&mut commands, &A { value: 1 });
+
+		let expected: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(B { value: 1 }),
+		];
+		assert!(commands == expected);
+	}
+}
+
+mod slice {
+	use super::*;
+
+	#[test]
+	fn references() {
+		let values: [&dyn MyTrait; 3] = [&A { value: 1 }, &B { value: 2 }, &A { value: 2 }];
+
+		assert!(dyn_eq::slice::contains(&values, &B { value: 2 }));
+		assert!(!dyn_eq::slice::contains(&values, &B { value: 1 }));
+		assert_eq!(
+			dyn_eq::slice::position_of(&values, &A { value: 2 }),
+			Some(2)
+		);
+		assert_eq!(dyn_eq::slice::position_of(&values, &5u8), None);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxes() {
+		let values: Vec<Box<dyn MyTrait>> =
+			vec![Box::new(A { value: 1 }), Box::new(B { value: 1 })];
+
+		assert!(dyn_eq::slice::contains(&values, &A { value: 1 }));
+		assert_eq!(
+			dyn_eq::slice::position_of(&values, &B { value: 1 }),
+			Some(1)
+		);
+	}
+
+	#[cfg(feature = "rc")]
+	#[test]
+	fn shared() {
+		let values: Vec<std::rc::Rc<dyn MyTrait>> = vec![std::rc::Rc::new(A { value: 1 })];
+
+		assert!(dyn_eq::slice::contains(&values, &A { value: 1 }));
+	}
+
+	#[test]
+	fn set_eq() {
+		let a: [&dyn MyTrait; 4] = [
+			&A { value: 1 },
+			&B { value: 1 },
+			&A { value: 1 },
+			&A { value: 2 },
+		];
+		let b: [&dyn MyTrait; 4] = [
+			&A { value: 2 },
+			&A { value: 1 },
+			&B { value: 1 },
+			&A { value: 1 },
+		];
+		let c: [&dyn MyTrait; 4] = [
+			&A { value: 2 },
+			&A { value: 1 },
+			&B { value: 1 },
+			&B { value: 1 },
+		];
+
+		assert!(dyn_eq::slice::set_eq(&a, &b));
+		assert!(dyn_eq::slice::set_eq(&b, &a));
+		assert!(!dyn_eq::slice::set_eq(&a, &c));
+		assert!(!dyn_eq::slice::set_eq(&c, &a));
+		assert!(!dyn_eq::slice::set_eq(&a, &b[1..]));
+		assert!(dyn_eq::slice::set_eq::<&dyn MyTrait, &dyn MyTrait>(
+			&[],
+			&[]
+		));
+	}
+}
+
+mod assertions {
+	use super::*;
+
+	trait Opaque: DynEq {}
+	dyn_eq::eq_trait_object!(Opaque);
+
+	impl Opaque for A {}
+	impl Opaque for B {}
+
+	fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+		let payload = std::panic::catch_unwind(f).unwrap_err();
+		payload
+			.downcast::<String>()
+			.map(|message| *message)
+			.unwrap()
+	}
+
+	#[test]
+	fn passing() {
+		let a: &dyn MyTrait = &A { value: 5 };
+		let b: &dyn MyTrait = &B { value: 5 };
+
+		dyn_eq::assert_dyn_eq!(*a, *a);
+		dyn_eq::assert_dyn_ne!(*a, *b);
+		dyn_eq::assert_dyn_ne!(*a, *b, "with a message: {}", 5);
+	}
+
+	#[cfg(not(feature = "pretty-diff"))]
+	#[test]
+	fn debug_output() {
+		let message = panic_message(|| {
+			let a: &dyn MyTrait = &A { value: 5 };
+			let b: &dyn MyTrait = &B { value: 5 };
+			dyn_eq::assert_dyn_eq!(*a, *b, "custom {}", "message");
+		});
+
+		assert!(message.starts_with("assertion `left == right` failed: custom message\n"));
+		assert!(message.contains("  left: A { value: 5 } (type "));
+		assert!(message.contains(" right: B { value: 5 } (type "));
+		#[cfg(feature = "type-name")]
+		assert!(message.contains("(type integration_test::A)"));
+	}
+
+	#[cfg(feature = "pretty-diff")]
+	#[test]
+	fn diff_output() {
+		let message = panic_message(|| {
+			let a: &dyn MyTrait = &A { value: 5 };
+			let b: &dyn MyTrait = &A { value: 6 };
+			dyn_eq::assert_dyn_eq!(*a, *b, "custom {}", "message");
+		});
+
+		assert!(message.starts_with("assertion `left == right` failed: custom message\n"));
+		assert!(message.contains("\n  left type: "));
+		assert!(
+			message.contains(
+				" A {\n\x1b[31m<    value: 5,\x1b[0m\n\x1b[32m>    value: 6,\x1b[0m\n }\n"
+			)
+		);
+	}
+
+	#[test]
+	fn without_debug() {
+		let message = panic_message(|| {
+			let a: &dyn Opaque = &A { value: 5 };
+			dyn_eq::assert_dyn_ne!(*a, *a);
+		});
+
+		assert!(message.starts_with("assertion `left != right` failed\n"));
+		assert!(message.contains("  left: <no Debug implementation> (type "));
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxes() {
+		let a: Box<dyn MyTrait> = Box::new(A { value: 5 });
+		let b: Box<dyn MyTrait> = Box::new(A { value: 5 });
+
+		dyn_eq::assert_dyn_eq!(*a, *b);
+		dyn_eq::assert_dyn_eq!(a, b);
+	}
+}
+
+#[cfg(feature = "alloc")]
+mod debug {
+	use super::*;
+
+	#[derive(Debug, PartialEq, Eq)]
+	struct DebugContainer {
+		field: Box<dyn MyTrait>,
+	}
+
+	#[test]
+	fn derived() {
+		let container = DebugContainer {
+			field: Box::new(A { value: 5 }),
+		};
+
+		assert_eq!(container, container);
+		assert_eq!(
+			format!("{container:?}"),
+			"DebugContainer { field: A { value: 5 } }"
+		);
+	}
+}
+
+#[cfg(feature = "unstable-specialization")]
+mod specialization {
+	use super::*;
+
+	#[test]
+	fn same_concrete_type() {
+		assert!(dyn_eq::eq(&A { value: 5 }, &A { value: 5 }));
+		assert!(dyn_eq::ne(&A { value: 5 }, &A { value: 6 }));
+		assert!(dyn_eq::ne(&A { value: 5 }, &B { value: 5 }));
+	}
+}
+
+mod zero_sized {
+	use super::*;
+
+	#[derive(Debug)]
+	struct Marker;
+
+	impl PartialEq for Marker {
+		fn eq(&self, _: &Self) -> bool {
+			panic!("zero-sized values shouldn't be compared")
+		}
+	}
+	impl Eq for Marker {}
+
+	#[derive(Debug, PartialEq, Eq)]
+	struct OtherMarker;
+
+	impl MyTrait for Marker {}
+	impl MyTrait for OtherMarker {}
+
+	#[test]
+	fn skips_eq() {
+		let a: &dyn MyTrait = &Marker;
+		let b: &dyn MyTrait = &Marker;
+		let c: &dyn MyTrait = &OtherMarker;
+
+		assert!(a == b);
+		assert!(a != c);
+	}
+}
+
+mod manual {
+	use std::cell::Cell;
+
+	use dyn_eq::{Manual, ManualEq};
+
+	use super::*;
+
+	#[derive(Debug)]
+	struct Cached {
+		key: u8,
+		hits: Cell<usize>,
+	}
+
+	impl ManualEq for Cached {
+		fn manual_eq(&self, other: &Self) -> bool {
+			self.key == other.key
+		}
+	}
+
+	// Compared ignoring its value
+	#[derive(Debug, PartialEq, Eq)]
+	struct Ignored(u8);
+
+	impl ManualEq for Ignored {
+		fn manual_eq(&self, _: &Self) -> bool {
+			true
+		}
+	}
+
+	impl MyTrait for Manual<Cached> {}
+	impl MyTrait for Manual<Ignored> {}
+
+	#[test]
+	fn custom_semantics() {
+		let a: &dyn MyTrait = &Manual(Cached {
+			key: 1,
+			hits: Cell::new(0),
+		});
+		let b: &dyn MyTrait = &Manual(Cached {
+			key: 1,
+			hits: Cell::new(3),
+		});
+		let c: &dyn MyTrait = &Manual(Cached {
+			key: 2,
+			hits: Cell::new(0),
+		});
+
+		assert!(a == b);
+		assert!(a != c);
+	}
+
+	#[test]
+	fn overrides_eq() {
+		let a: &dyn MyTrait = &Manual(Ignored(1));
+		let b: &dyn MyTrait = &Manual(Ignored(2));
+		let c: &dyn MyTrait = &A { value: 1 };
+
+		assert!(a == b);
+		assert!(a != c);
+	}
+
+	#[test]
+	fn deref() {
+		let mut value = Manual(Cached {
+			key: 1,
+			hits: Cell::new(0),
+		});
+		value.key = 2;
+		value.hits.set(1);
+
+		assert_eq!(value.0.key, 2);
+		assert_eq!(value.hits.get(), 1);
+	}
+}
+
+#[cfg(feature = "core-error")]
+mod error {
+	use core::fmt;
+
+	use dyn_eq::DynError;
+
+	#[derive(Debug, PartialEq, Eq)]
+	struct ParseError(u8);
+
+	impl fmt::Display for ParseError {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			write!(f, "invalid byte {}", self.0)
+		}
+	}
+
+	impl std::error::Error for ParseError {}
+
+	#[derive(Debug, PartialEq, Eq)]
+	struct IoError;
+
+	impl fmt::Display for IoError {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			f.write_str("io error")
+		}
+	}
+
+	impl std::error::Error for IoError {}
+
+	#[test]
+	fn references() {
+		let a: &dyn DynError = &ParseError(1);
+		let b: &dyn DynError = &ParseError(1);
+		let c: &dyn DynError = &IoError;
+
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+		assert_eq!(a.to_string(), "invalid byte 1");
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let a: Result<(), Box<dyn DynError + Send + Sync>> = Err(Box::new(ParseError(1)));
+		let b: Result<(), Box<dyn DynError + Send + Sync>> = Err(Box::new(ParseError(1)));
+		let c: Result<(), Box<dyn DynError + Send + Sync>> = Err(Box::new(IoError));
+
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+	}
+}
+
+#[cfg(feature = "alloc")]
+mod wrapper {
+	use dyn_eq::DynEqWrapper;
+
+	use super::*;
+
+	// A trait that can't have `DynEq` as a supertrait
+	trait Foreign {
+		fn value(&self) -> u32;
+		fn set_value(&mut self, value: u32);
+	}
+
+	impl Foreign for A {
+		fn value(&self) -> u32 {
+			self.value
+		}
+
+		fn set_value(&mut self, value: u32) {
+			self.value = value;
+		}
+	}
+
+	impl Foreign for B {
+		fn value(&self) -> u32 {
+			self.value
+		}
+
+		fn set_value(&mut self, value: u32) {
+			self.value = value;
+		}
+	}
+
+	#[derive(PartialEq, Eq)]
+	struct Container {
+		field: DynEqWrapper<dyn Foreign>,
+	}
+
+	#[test]
+	fn comparison() {
+		let a1 = dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 5 });
+		let a2 = dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 5 });
+		let a3 = dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 6 });
+		let b = dyn_eq::wrap_eq_trait_object!(dyn Foreign, B { value: 5 });
+
+		assert!(a1 == a2);
+		assert!(a1 != a3);
+		assert!(a1 != b);
+		assert_eq!(a1.value(), b.value());
+	}
+
+	#[test]
+	fn mutation() {
+		let mut a1 = dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 5 });
+		let a2 = dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 6 });
+
+		a1.set_value(6);
+		assert!(a1 == a2);
+	}
+
+	#[test]
+	fn derive() {
+		let c1 = Container {
+			field: dyn_eq::wrap_eq_trait_object!(dyn Foreign, A { value: 5 }),
+		};
+		let c2 = Container {
+			field: DynEqWrapper::<dyn Foreign>::new(A { value: 5 }, |value| value, |value| value),
+		};
+
+		assert!(c1 == c2);
+	}
+
+	#[test]
+	fn send_sync() {
+		fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+		let a = dyn_eq::wrap_eq_trait_object!(dyn Foreign + Send + Sync, A { value: 5 });
+		assert_send_sync(&a);
+		std::thread::spawn(move || assert_eq!(a.value(), 5))
+			.join()
+			.unwrap();
+	}
+
+	#[derive(PartialEq, Eq, Debug)]
+	struct Shared(std::rc::Rc<u32>);
+
+	impl Foreign for Shared {
+		fn value(&self) -> u32 {
+			*self.0
+		}
+
+		fn set_value(&mut self, value: u32) {
+			*std::rc::Rc::make_mut(&mut self.0) = value;
+		}
+	}
+
+	#[test]
+	fn not_send() {
+		use std::{any::Any, rc::Rc};
+
+		use dyn_eq::DynEqWrapper;
+
+		let shared = Rc::new(5);
+		let mut a: DynEqWrapper<dyn Foreign, dyn Any> =
+			dyn_eq::wrap_eq_trait_object!(dyn Foreign, Shared(shared.clone()), markers = dyn Any);
+		let b = dyn_eq::wrap_eq_trait_object!(dyn Foreign, Shared(shared), markers = dyn Any);
+		assert!(a == b);
+
+		a.set_value(6);
+		assert_eq!(a.value(), 6);
+		assert_eq!(b.value(), 5);
+		assert!(a != b);
+	}
+}
+
+mod total {
+	use dyn_eq::Total;
+
+	use super::*;
+
+	#[derive(Debug, PartialEq)]
+	struct Point {
+		x: f64,
+		y: f64,
+	}
+
+	impl MyTrait for Total<Point> {}
+	impl MyTrait for Total<f32> {}
+
+	#[test]
+	fn floats() {
+		assert_eq!(Total(1.0), Total(1.0));
+		assert_eq!(Total(0.0), Total(-0.0));
+		assert_eq!(Total(f64::NAN), Total(-f64::NAN));
+		assert_ne!(Total(f64::NAN), Total(1.0));
+		assert_ne!(Total(1.0), Total(f64::NAN));
+	}
+
+	#[test]
+	fn trait_objects() {
+		let a: &dyn MyTrait = &Total(Point { x: 1.0, y: 2.0 });
+		let b: &dyn MyTrait = &Total(Point { x: 1.0, y: 2.0 });
+		let c: &dyn MyTrait = &Total(Point { x: 1.0, y: 3.0 });
+		let nan: &dyn MyTrait = &Total(Point {
+			x: f64::NAN,
+			y: 2.0,
+		});
+		let float: &dyn MyTrait = &Total(1.0f32);
+
+		assert!(a == b);
+		assert!(a != c);
+		assert!(a != nan);
+		assert!(nan == nan);
+		assert!(a != float);
+	}
+}
+
+mod enum_dispatch {
+	use super::*;
+
+	#[derive(PartialEq, Eq)]
+	enum Value {
+		A(A),
+		B(B),
+	}
+
+	dyn_eq::enum_eq_trait_object!(MyTrait, self::Value { A, B });
+
+	#[test]
+	fn trait_objects() {
+		let a: &dyn MyTrait = &A { value: 1 };
+
+		assert!(*a == Value::A(A { value: 1 }));
+		assert!(*a != Value::A(A { value: 2 }));
+		assert!(*a != Value::B(B { value: 1 }));
+		assert!(Value::A(A { value: 1 }) == *a);
+		assert!(Value::B(B { value: 1 }) != *a);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxes() {
+		let b: Box<dyn MyTrait> = Box::new(B { value: 1 });
+
+		assert!(b == Value::B(B { value: 1 }));
+		assert!(b != Value::A(A { value: 1 }));
+		assert!(Value::B(B { value: 1 }) == b);
+	}
+}
+
+mod cross_traits {
+	use super::*;
+
+	trait Request: DynEq {}
+	trait CachedRequest: DynEq {}
+	dyn_eq::eq_trait_object!(Request, CachedRequest);
+	dyn_eq::cross_eq_trait_object!(Request, CachedRequest);
+
+	impl Request for A {}
+	impl Request for B {}
+	impl CachedRequest for A {}
+
+	#[test]
+	fn references() {
+		let a: &dyn Request = &A { value: 5 };
+		let b: &dyn Request = &B { value: 5 };
+		let cached: &dyn CachedRequest = &A { value: 5 };
+
+		assert!(a == cached);
+		assert!(cached == a);
+		assert!(b != cached);
+		assert!(cached != b);
+	}
+
+	#[test]
+	fn markers() {
+		let a: &(dyn Request + Send + Sync) = &A { value: 5 };
+		let cached: &(dyn CachedRequest + Send + Sync) = &A { value: 6 };
+
+		assert!(a != cached);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let a: Box<dyn Request> = Box::new(A { value: 5 });
+		let cached: Box<dyn CachedRequest> = Box::new(A { value: 5 });
+
+		assert!(a == cached);
+		assert!(cached == a);
+	}
+}
+
+#[cfg(feature = "trait-upcasting")]
+mod upcast {
+	use dyn_eq::CrossTypeEq;
+
+	use super::*;
+
+	#[derive(PartialEq, Eq)]
+	struct Wrapped(A);
+
+	impl CrossTypeEq<A> for Wrapped {
+		fn cross_eq(&self, other: &A) -> bool {
+			self.0 == *other
+		}
+	}
+
+	trait Shape: DynEq {}
+	dyn_eq::eq_trait_object!(Shape; cross_type(Wrapped = A));
+
+	trait Polygon: Shape {}
+	dyn_eq::eq_trait_object!(Polygon);
+	dyn_eq::upcast_eq_trait_object!(Polygon: Shape);
+
+	impl Shape for A {}
+	impl Shape for B {}
+	impl Shape for Wrapped {}
+	impl Polygon for A {}
+
+	#[test]
+	fn references() {
+		let polygon: &dyn Polygon = &A { value: 5 };
+		let a: &dyn Shape = &A { value: 5 };
+		let b: &dyn Shape = &B { value: 5 };
+
+		assert!(polygon == a);
+		assert!(a == polygon);
+		assert!(polygon != b);
+		assert!(b != polygon);
+	}
+
+	#[test]
+	fn supertrait_options() {
+		let polygon: &dyn Polygon = &A { value: 5 };
+		let wrapped: &dyn Shape = &Wrapped(A { value: 5 });
+
+		assert!(polygon == wrapped);
+		assert!(wrapped == polygon);
+	}
+
+	#[test]
+	fn markers() {
+		let polygon: &(dyn Polygon + Send + Sync) = &A { value: 5 };
+		let a: &(dyn Shape + Send + Sync) = &A { value: 6 };
+
+		assert!(polygon != a);
+		assert!(a != polygon);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxed() {
+		let polygon: Box<dyn Polygon> = Box::new(A { value: 5 });
+		let a: Box<dyn Shape> = Box::new(A { value: 5 });
+
+		assert!(polygon == a);
+		assert!(a == polygon);
+	}
+}
+
+mod cross_type {
+	use dyn_eq::CrossTypeEq;
+
+	use super::*;
+
+	#[derive(PartialEq, Eq)]
+	struct Legacy {
+		name: &'static str,
+	}
+
+	#[derive(PartialEq, Eq)]
+	struct Current {
+		name: &'static str,
+		version: u8,
+	}
+
+	impl CrossTypeEq<Current> for Legacy {
+		fn cross_eq(&self, other: &Current) -> bool {
+			other.version == 1 && self.name == other.name
+		}
+	}
+
+	trait Config: DynEq {}
+	dyn_eq::eq_trait_object!(Config; cross_type(Legacy = Current), ptr_eq);
+
+	impl Config for Legacy {}
+	impl Config for Current {}
+	impl Config for A {}
+
+	#[test]
+	fn both_directions() {
+		let legacy: &dyn Config = &Legacy { name: "a" };
+		let current: &dyn Config = &Current {
+			name: "a",
+			version: 1,
+		};
+		let newer: &dyn Config = &Current {
+			name: "a",
+			version: 2,
+		};
+
+		assert!(legacy == current);
+		assert!(current == legacy);
+		assert!(legacy != newer);
+		assert!(newer != legacy);
+	}
+
+	#[test]
+	fn other_types() {
+		let legacy: &dyn Config = &Legacy { name: "a" };
+		let a: &dyn Config = &A { value: 1 };
+
+		assert!(legacy == legacy);
+		assert!(legacy != a);
+		assert!(a != legacy);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn markers() {
+		let legacy: Box<dyn Config + Send + Sync> = Box::new(Legacy { name: "a" });
+		let current: Box<dyn Config> = Box::new(Current {
+			name: "a",
+			version: 1,
+		});
+
+		assert!(legacy == current);
+	}
+}
+
+mod matchers {
+	use dyn_eq::matchers::{AnyOfType, Predicate};
+
+	use super::*;
+
+	fn is_small(value: &A) -> bool {
+		value.value < 10
+	}
+
+	#[test]
+	fn any_of_type() {
+		let any = AnyOfType::<A>::new();
+
+		assert!(any.matches(&(&A { value: 1 } as &dyn MyTrait)));
+		#[cfg(feature = "alloc")]
+		assert!(any.matches(&(Box::new(A { value: 2 }) as Box<dyn MyTrait>)));
+		assert!(!any.matches(&(&B { value: 1 } as &dyn MyTrait)));
+		assert_eq!(any, AnyOfType::default());
+	}
+
+	#[test]
+	fn predicate() {
+		let small = Predicate::new(is_small);
+
+		assert!(small.matches_dyn(&(&A { value: 1 } as &dyn MyTrait)));
+		assert!(!small.matches_dyn(&(&A { value: 10 } as &dyn MyTrait)));
+		assert!(!small.matches_dyn(&(&B { value: 1 } as &dyn MyTrait)));
+
+		assert_eq!(small, Predicate::new(is_small));
+		assert!(small.matches(&A { value: 5 }));
+	}
+}
+
+// The strict checks do additional comparisons, which would be counted
+#[cfg(not(feature = "strict-checks"))]
+mod custom_ne {
+	use core::sync::atomic::{AtomicUsize, Ordering};
+
+	use super::*;
+
+	static NE_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(Debug)]
+	struct CheapNe(u8);
+
+	impl PartialEq for CheapNe {
+		fn eq(&self, other: &Self) -> bool {
+			self.0 == other.0
+		}
+
+		#[allow(clippy::partialeq_ne_impl)]
+		fn ne(&self, other: &Self) -> bool {
+			NE_CALLS.fetch_add(1, Ordering::Relaxed);
+			self.0 != other.0
+		}
+	}
+	impl Eq for CheapNe {}
+
+	impl MyTrait for CheapNe {}
+
+	// The counter is shared, so everything is checked in a single test
+	#[test]
+	fn forwards_ne() {
+		let a: &dyn MyTrait = &CheapNe(1);
+		let b: &dyn MyTrait = &CheapNe(2);
+		let c: &dyn MyTrait = &A { value: 1 };
+
+		assert!(a != b);
+		assert_eq!(NE_CALLS.load(Ordering::Relaxed), 1);
+		assert!(a == a);
+		assert_eq!(NE_CALLS.load(Ordering::Relaxed), 1);
+
+		// Different types are different without calling `ne`
+		assert!(a != c);
+		assert_eq!(NE_CALLS.load(Ordering::Relaxed), 1);
+
+		// Other marker combinations delegate to the plain trait object
+		let a: &(dyn MyTrait + Send) = &CheapNe(1);
+		let b: &(dyn MyTrait + Send) = &CheapNe(1);
+		let c: &(dyn MyTrait + Sync) = &CheapNe(2);
+		assert!(!a.ne(b));
+		assert!(a != c);
+		assert_eq!(NE_CALLS.load(Ordering::Relaxed), 3);
+
+		// Boxes forward to the trait object
+		#[cfg(feature = "alloc")]
+		{
+			let a: Box<dyn MyTrait> = Box::new(CheapNe(1));
+			let b: Box<dyn MyTrait> = Box::new(CheapNe(2));
+			assert!(a != b);
+			assert_eq!(NE_CALLS.load(Ordering::Relaxed), 4);
+		}
+	}
+}
+
+mod pointers {
+	use core::{marker::PhantomData, ops::Deref};
+
+	use super::*;
+
+	// Stands for an inline container with a size parameter, such as `smallbox::SmallBox`
+	struct Inline<T: ?Sized + 'static, Space> {
+		value: &'static T,
+		space: PhantomData<Space>,
+	}
+
+	impl<T: ?Sized, Space> Inline<T, Space> {
+		fn new(value: &'static T) -> Self {
+			Self {
+				value,
+				space: PhantomData,
+			}
+		}
+	}
+
+	impl<T: ?Sized, Space> Deref for Inline<T, Space> {
+		type Target = T;
+
+		fn deref(&self) -> &T {
+			self.value
+		}
+	}
+
+	impl<T: ?Sized + PartialEq, Space> PartialEq for Inline<T, Space> {
+		fn eq(&self, other: &Self) -> bool {
+			**self == **other
+		}
+	}
+
+	impl<T: ?Sized + Eq, Space> Eq for Inline<T, Space> {}
+
+	struct S4;
+	type Small<T> = Inline<T, S4>;
+
+	trait Widget: DynEq {}
+	dyn_eq::eq_trait_object!(Widget; pointers(self::Small));
+
+	impl Widget for A {}
+	impl Widget for B {}
+
+	#[derive(PartialEq, Eq)]
+	struct Panel {
+		widget: Small<dyn Widget + Send + Sync>,
+	}
+
+	#[test]
+	fn derive() {
+		let a = Panel {
+			widget: Small::new(&A { value: 1 }),
+		};
+		let b = Panel {
+			widget: Small::new(&A { value: 1 }),
+		};
+		let c = Panel {
+			widget: Small::new(&B { value: 1 }),
+		};
+		assert!(a == b);
+		assert!(a != c);
+	}
+
+	#[test]
+	fn pointee() {
+		let a: Small<dyn Widget> = Small::new(&A { value: 1 });
+		let b: &dyn Widget = &A { value: 1 };
+		let c: &dyn Widget = &A { value: 2 };
+		assert!(a == *b);
+		assert!(*b == a);
+		assert!(a != *c);
+		assert!(*c != a);
+	}
+}
+
+mod deref {
+	use core::ops::Deref;
+
+	use super::*;
+
+	struct Handle<T: ?Sized + 'static>(&'static T);
+
+	impl<T: ?Sized> Deref for Handle<T> {
+		type Target = T;
+
+		fn deref(&self) -> &T {
+			self.0
+		}
+	}
+
+	dyn_eq::deref_eq_trait_object!(MyTrait, Handle);
+
+	#[derive(PartialEq, Eq)]
+	struct Graph {
+		node: Handle<dyn MyTrait + Send>,
+	}
+
+	#[test]
+	fn derive() {
+		let a = Graph {
+			node: Handle(&A { value: 1 }),
+		};
+		let b = Graph {
+			node: Handle(&A { value: 1 }),
+		};
+		let c = Graph {
+			node: Handle(&B { value: 1 }),
+		};
+		assert!(a == b);
+		assert!(a != c);
+	}
+
+	#[test]
+	fn pointers_and_pointees() {
+		let a: Handle<dyn MyTrait> = Handle(&A { value: 1 });
+		let b: Handle<dyn MyTrait + Send + Sync> = Handle(&A { value: 1 });
+		let c: &dyn MyTrait = &A { value: 2 };
+		assert!(a == b);
+		assert!(b == a);
+		assert!(a != *c);
+		assert!(*c != a);
+	}
+}
+
+#[cfg(feature = "rc")]
+mod weak {
+	use std::rc::{Rc, Weak};
+
+	use super::*;
+
+	#[test]
+	fn live() {
+		let a: Rc<dyn MyTrait> = Rc::new(A { value: 1 });
+		let b: Rc<dyn MyTrait> = Rc::new(A { value: 1 });
+		let c: Rc<dyn MyTrait> = Rc::new(B { value: 1 });
+		assert!(dyn_eq::weak::rc_eq(&Rc::downgrade(&a), &Rc::downgrade(&b)));
+		assert!(!dyn_eq::weak::rc_eq(&Rc::downgrade(&a), &Rc::downgrade(&c)));
+	}
+
+	#[test]
+	fn dead() {
+		let a: Rc<dyn MyTrait> = Rc::new(A { value: 1 });
+		let b: Rc<dyn MyTrait> = Rc::new(A { value: 1 });
+		let (weak_a, weak_b) = (Rc::downgrade(&a), Rc::downgrade(&b));
+		drop(a);
+		assert!(!dyn_eq::weak::rc_eq(&weak_a, &weak_b));
+		assert!(!dyn_eq::weak::rc_eq(&weak_b, &weak_a));
+		assert!(dyn_eq::weak::rc_eq(&weak_a, &weak_a.clone()));
+
+		drop(b);
+		assert!(!dyn_eq::weak::rc_eq(&weak_a, &weak_b));
+		let never: Weak<dyn MyTrait> = Weak::<A>::new();
+		assert!(!dyn_eq::weak::rc_eq(&weak_a, &never));
+	}
+
+	#[cfg(feature = "arc")]
+	#[test]
+	fn arc() {
+		use std::sync::Arc;
+
+		let a: Arc<dyn MyTrait + Send + Sync> = Arc::new(A { value: 1 });
+		let b: Arc<dyn MyTrait + Send + Sync> = Arc::new(A { value: 2 });
+		assert!(dyn_eq::weak::arc_eq(
+			&Arc::downgrade(&a),
+			&Arc::downgrade(&a)
+		));
+		assert!(!dyn_eq::weak::arc_eq(
+			&Arc::downgrade(&a),
+			&Arc::downgrade(&b)
+		));
+	}
+}
+
+// Nothing from this crate or from the prelude is in scope, and some of their names are shadowed
+mod hygiene {
+	#![allow(dead_code)]
+
+	mod core {}
+	mod alloc {}
+	struct Box;
+	struct Rc;
+	trait PartialEq {}
+	trait Eq {}
+	trait DynEq {}
+	trait Any {}
+
+	trait Isolated: ::dyn_eq::DynEq {}
+	::dyn_eq::eq_trait_object!(Isolated; ptr_eq);
+
+	#[derive(::core::cmp::PartialEq, ::core::cmp::Eq)]
+	struct Value(u8);
+	impl Isolated for Value {}
+
+	#[test]
+	fn no_imports() {
+		let a: &dyn Isolated = &Value(1);
+		let b: &dyn Isolated = &Value(1);
+		let c: &(dyn Isolated + Send) = &Value(2);
+		assert!(a == b);
+		assert!(c != a);
+	}
+}
+
+#[cfg(feature = "collections")]
+mod interner {
+	use dyn_eq::DynInterner;
+
+	use super::*;
+
+	#[test]
+	fn ids() {
+		let mut interner: DynInterner<dyn MyTrait> = DynInterner::new();
+		assert!(interner.is_empty());
+
+		let a = interner.intern(Box::new(A { value: 1 }));
+		let b = interner.intern(Box::new(B { value: 1 }));
+		assert_eq!(interner.intern(Box::new(A { value: 1 })), a);
+		assert_eq!(interner.intern(Box::new(B { value: 1 })), b);
+		assert_ne!(a, b);
+
+		assert_eq!(interner.len(), 2);
+		assert_eq!((a.index(), b.index()), (0, 1));
+		assert_eq!(interner.get(&A { value: 1 }), Some(a));
+		assert_eq!(interner.get(&A { value: 2 }), None);
+		assert!(interner.resolve(b) == &B { value: 1 } as &dyn MyTrait);
+	}
+
+	#[test]
+	fn iteration_order() {
+		let mut interner: DynInterner<dyn MyTrait> = DynInterner::new();
+		let b = interner.intern(Box::new(B { value: 1 }));
+		let a = interner.intern(Box::new(A { value: 1 }));
+		interner.intern(Box::new(B { value: 1 }));
+
+		let ids: Vec<_> = interner.iter().map(|(id, _)| id).collect();
+		assert_eq!(ids, [b, a]);
+	}
+}
+
+#[cfg(feature = "collections")]
+mod batch {
+	use super::*;
+
+	#[test]
+	fn group_eq() {
+		let values: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(A { value: 1 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 1 }),
+			Box::new(A { value: 1 }),
+		];
+		assert_eq!(
+			dyn_eq::batch::group_eq(&values),
+			[vec![0, 4, 5], vec![1, 3], vec![2]]
+		);
+	}
+
+	#[test]
+	fn empty() {
+		let values: [&dyn MyTrait; 0] = [];
+		assert!(dyn_eq::batch::group_eq(&values).is_empty());
+	}
+
+	#[test]
+	fn group() {
+		let values: Vec<Box<dyn MyTrait>> = vec![
+			Box::new(A { value: 1 }),
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(A { value: 1 }),
+		];
+		let groups = dyn_eq::batch::group(values);
+
+		let expected: Vec<Vec<Box<dyn MyTrait>>> = vec![
+			vec![Box::new(A { value: 1 }), Box::new(A { value: 1 })],
+			vec![Box::new(B { value: 1 })],
+			vec![Box::new(A { value: 2 })],
+		];
+		assert!(groups == expected);
+	}
+
+	#[test]
+	fn group_iter() {
+		let values: Vec<&dyn MyTrait> = vec![&A { value: 1 }, &A { value: 1 }, &B { value: 1 }];
+		let mut groups = dyn_eq::batch::group_iter(values);
+
+		assert_eq!(groups.next().map(|group| group.len()), Some(2));
+		assert_eq!(groups.next().map(|group| group.len()), Some(1));
+		assert!(groups.next().is_none());
+	}
+}
+
+mod changed {
+	use dyn_eq::Changed;
+
+	use super::*;
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxes() {
+		let mut state: Changed<Box<dyn MyTrait>> = Changed::new(Box::new(A { value: 1 }));
+
+		assert!(!state.set(Box::new(A { value: 1 })));
+		assert!(state.set(Box::new(B { value: 1 })));
+		assert!(state.set(Box::new(B { value: 2 })));
+		assert!((**state).eq_concrete(&B { value: 2 }));
+	}
+
+	#[test]
+	fn pointer_fast_path() {
+		static SHARED: A = A { value: 1 };
+
+		let mut state: Changed<&dyn MyTrait> = Changed::new(&SHARED);
+
+		assert!(!state.set_ptr(&SHARED));
+		assert!(!state.set_ptr(&A { value: 1 }));
+		// Promoted constants with the same bytes can share an address, so the types are compared too
+		assert!(state.set_ptr(&B { value: 1 }));
+		assert!(state.into_inner().eq_concrete(&B { value: 1 }));
+	}
+}
+
+#[cfg(feature = "collections")]
+mod counter {
+	use dyn_eq::DynCounter;
+
+	use super::*;
+
+	#[test]
+	fn counts() {
+		let mut counter: DynCounter<dyn MyTrait> = DynCounter::new();
+		assert!(counter.is_empty());
+
+		assert_eq!(counter.add(Box::new(A { value: 1 })), 1);
+		assert_eq!(counter.add(Box::new(B { value: 1 })), 1);
+		assert_eq!(counter.add(Box::new(A { value: 1 })), 2);
+		assert_eq!(counter.add(Box::new(B { value: 2 })), 1);
+		assert_eq!(counter.add(Box::new(B { value: 2 })), 2);
+		assert_eq!(counter.add(Box::new(B { value: 2 })), 3);
+
+		assert_eq!(counter.len(), 3);
+		assert_eq!(counter.total(), 6);
+		assert_eq!(counter.count_of(&A { value: 1 }), 2);
+		assert_eq!(counter.count_of(&B { value: 1 }), 1);
+		assert_eq!(counter.count_of(&A { value: 2 }), 0);
+	}
+
+	#[test]
+	fn ordering() {
+		let counter: DynCounter<dyn MyTrait> = [
+			Box::new(A { value: 1 }) as Box<dyn MyTrait>,
+			Box::new(B { value: 1 }),
+			Box::new(A { value: 2 }),
+			Box::new(A { value: 2 }),
+		]
+		.into_iter()
+		.collect();
+
+		let counts: Vec<usize> = counter.iter().map(|(_, count)| count).collect();
+		assert_eq!(counts, [1, 1, 2]);
+		assert!(counter.iter().next().unwrap().0 == &A { value: 1 } as &dyn MyTrait);
+
+		let most_common = counter.most_common();
+		assert!(most_common[0].0 == &A { value: 2 } as &dyn MyTrait);
+		// Ties keep the insertion order
+		assert!(most_common[1].0 == &A { value: 1 } as &dyn MyTrait);
+		assert!(most_common[2].0 == &B { value: 1 } as &dyn MyTrait);
+	}
+}
+
+#[cfg(feature = "collections")]
+mod map {
+	use dyn_eq::DynMap;
+
+	use super::*;
+
+	#[test]
+	fn insert_and_get() {
+		let mut map: DynMap<dyn MyTrait, &str> = DynMap::new();
+		assert!(map.is_empty());
+
+		assert_eq!(map.insert(Box::new(A { value: 1 }), "a"), None);
+		assert_eq!(map.insert(Box::new(B { value: 1 }), "b"), None);
+		assert_eq!(map.insert(Box::new(A { value: 1 }), "c"), Some("a"));
+
+		assert_eq!(map.len(), 2);
+		assert_eq!(map.get(&A { value: 1 }), Some(&"c"));
+		assert_eq!(map.get(&B { value: 1 }), Some(&"b"));
+		assert_eq!(map.get(&A { value: 2 }), None);
+		assert!(map.contains_key(&B { value: 1 }));
+
+		*map.get_mut(&B { value: 1 }).unwrap() = "d";
+		assert_eq!(map.remove(&B { value: 1 }), Some("d"));
+		assert_eq!(map.remove(&B { value: 1 }), None);
+		assert_eq!(map.values().collect::<Vec<_>>(), [&"c"]);
+	}
+
+	#[test]
+	fn entry() {
+		let mut map: DynMap<dyn MyTrait, usize> = DynMap::new();
+		for value in [1, 2, 1, 1] {
+			*map.entry(Box::new(A { value })).or_default() += 1;
+		}
+		map.entry(Box::new(A { value: 2 }))
+			.and_modify(|count| *count *= 10)
+			.or_insert_with(|| unreachable!());
+
+		let entry = map.entry(Box::new(B { value: 1 }));
+		assert!(entry.key() == &B { value: 1 } as &dyn MyTrait);
+		assert_eq!(entry.get(), None);
+		assert_eq!(*entry.or_insert(5), 5);
+
+		let counts: Vec<usize> = map.values().copied().collect();
+		assert_eq!(counts, [3, 10, 5]);
+		assert!(map.keys().next().unwrap() == &A { value: 1 } as &dyn MyTrait);
+	}
+}
+
+#[cfg(all(feature = "collections", not(feature = "strict-checks")))]
+mod cache {
+	use core::sync::atomic::{AtomicUsize, Ordering};
+
+	use dyn_eq::EqCache;
+
+	use super::*;
+
+	static EQ_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(Debug)]
+	struct Expensive(Vec<u8>);
+
+	impl PartialEq for Expensive {
+		fn eq(&self, other: &Self) -> bool {
+			EQ_CALLS.fetch_add(1, Ordering::Relaxed);
+			self.0 == other.0
+		}
+	}
+	impl Eq for Expensive {}
+
+	impl MyTrait for Expensive {}
+
+	// The counter is shared, so everything is checked in a single test
+	#[test]
+	fn memoizes() {
+		let mut a: Box<dyn MyTrait> = Box::new(Expensive(vec![1, 2, 3]));
+		let b: Box<dyn MyTrait> = Box::new(Expensive(vec![1, 2, 3]));
+		let c: Box<dyn MyTrait> = Box::new(A { value: 1 });
+		let mut cache = EqCache::new();
+
+		assert!(cache.eq(&*a, &*b));
+		assert!(cache.eq(&*b, &*a));
+		assert!(!cache.ne(&*a, &*b));
+		assert_eq!(EQ_CALLS.load(Ordering::Relaxed), 1);
+		assert_eq!(cache.len(), 1);
+
+		// Neither different types nor a value with itself need a comparison
+		assert!(cache.ne(&*a, &*c));
+		assert!(cache.eq(&*a, &*a));
+		assert_eq!(EQ_CALLS.load(Ordering::Relaxed), 1);
+		assert_eq!(cache.len(), 1);
+
+		dyn_eq::downcast_mut::<Expensive>(&mut a).unwrap().0.push(4);
+		cache.invalidate(&*a);
+		assert!(cache.is_empty());
+		assert!(cache.ne(&*a, &*b));
+		assert_eq!(EQ_CALLS.load(Ordering::Relaxed), 2);
+
+		cache.clear();
+		assert!(cache.is_empty());
+	}
+
+	#[test]
+	fn same_address() {
+		#[derive(Debug, PartialEq, Eq)]
+		struct Outer {
+			inner: A,
+		}
+		impl MyTrait for Outer {}
+
+		let outer = Outer {
+			inner: A { value: 1 },
+		};
+		let mut cache = EqCache::new();
+
+		assert!(cache.ne(&outer as &dyn MyTrait, &outer.inner as &dyn MyTrait));
+		assert!(cache.eq(
+			&outer.inner as &dyn MyTrait,
+			&A { value: 1 } as &dyn MyTrait
+		));
+	}
+}
+
+mod diff {
+	use core::any::TypeId;
+
+	use dyn_eq::{DynComparison, DynDifference};
+
+	use super::*;
+
+	#[test]
+	fn equal() {
+		let a: &dyn MyTrait = &A { value: 1 };
+		let b: &dyn MyTrait = &A { value: 1 };
+		assert!(dyn_eq::diff(&a, &b).is_none());
+	}
+
+	#[test]
+	fn different_values() {
+		let a: &dyn MyTrait = &A { value: 1 };
+		let b: &dyn MyTrait = &A { value: 2 };
+		let Some(DynDifference::Value { left, right }) = dyn_eq::diff(&a, &b) else {
+			panic!("the values should have different contents");
+		};
+		assert!(std::ptr::addr_eq(left, a) && std::ptr::addr_eq(right, b));
+
+		let report = dyn_eq::diff(&a, &b).unwrap().to_string();
+		assert!(report.starts_with("different values of type"));
+		assert!(report.contains("left: A { value: 1 }"));
+		assert!(report.contains("right: A { value: 2 }"));
+	}
+
+	#[test]
+	fn different_types() {
+		let a: &dyn MyTrait = &A { value: 1 };
+		let b: &dyn MyTrait = &B { value: 1 };
+		assert!(matches!(
+			dyn_eq::diff(&a, &b),
+			Some(DynDifference::Type { .. })
+		));
+
+		let report = dyn_eq::diff(&a, &b).unwrap().to_string();
+		assert!(report.starts_with("different types"));
+		#[cfg(feature = "type-name")]
+		assert!(report.contains("(type integration_test::B)"));
+	}
+
+	#[test]
+	fn compare() {
+		let a: &dyn MyTrait = &A { value: 1 };
+		let b: &dyn MyTrait = &A { value: 1 };
+		let c: &dyn MyTrait = &A { value: 2 };
+		let d: &(dyn MyTrait + Send) = &B { value: 1 };
+
+		assert_eq!(dyn_eq::compare(&a, &b), DynComparison::Equal);
+		assert!(dyn_eq::compare(&a, &b).is_eq());
+		assert_eq!(dyn_eq::compare(&a, &c), DynComparison::NotEqual);
+		assert!(!dyn_eq::compare(&a, &c).is_eq());
+		assert_eq!(
+			dyn_eq::compare(&a, &(d as &dyn MyTrait)),
+			DynComparison::DifferentType {
+				left: TypeId::of::<A>(),
+				right: TypeId::of::<B>(),
+			}
+		);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn boxes() {
+		let a: Box<dyn MyTrait> = Box::new(A { value: 1 });
+		let b: Box<dyn MyTrait> = Box::new(B { value: 1 });
+
+		assert!(matches!(
+			dyn_eq::diff(&a, &b),
+			Some(DynDifference::Type { .. })
+		));
+		assert_eq!(
+			dyn_eq::compare(&a, &b),
+			DynComparison::DifferentType {
+				left: TypeId::of::<A>(),
+				right: TypeId::of::<B>(),
+			}
+		);
+	}
+}
+
+#[cfg(all(feature = "strict-checks", debug_assertions))]
+mod strict_checks {
+	use super::*;
+
+	#[derive(Debug)]
+	struct Asymmetric(u8);
+
+	impl PartialEq for Asymmetric {
+		fn eq(&self, other: &Self) -> bool {
+			self.0 <= other.0
+		}
+	}
+	impl Eq for Asymmetric {}
+
+	impl MyTrait for Asymmetric {}
+
+	#[test]
+	fn lawful() {
+		let a: &dyn MyTrait = &A { value: 1 };
+		let b: &dyn MyTrait = &A { value: 2 };
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	#[should_panic = "is not symmetric"]
+	fn not_symmetric() {
+		let a: &dyn MyTrait = &Asymmetric(1);
+		let b: &dyn MyTrait = &Asymmetric(2);
+		let _ = a == b;
+	}
+
+	#[cfg(feature = "alloc")]
+	#[derive(PartialEq, Eq, Debug)]
+	struct Nested(Option<Box<dyn MyTrait>>);
+
+	#[cfg(feature = "alloc")]
+	impl MyTrait for Nested {}
+
+	#[cfg(feature = "alloc")]
+	fn nest(levels: usize, inner: Box<dyn MyTrait>) -> Box<dyn MyTrait> {
+		(0..levels).fold(inner, |value, _| Box::new(Nested(Some(value))))
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn deeply_nested() {
+		let a = nest(64, Box::new(A { value: 1 }));
+		let b = nest(64, Box::new(A { value: 2 }));
+		assert!(a == a);
+		assert!(a != b);
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	#[should_panic = "is not symmetric"]
+	fn nested_not_symmetric() {
+		let a = nest(4, Box::new(Asymmetric(1)));
+		let b = nest(4, Box::new(Asymmetric(2)));
+		let _ = a == b;
+	}
+}
+
+#[cfg(feature = "alloc")]
+mod any_eq {
+	use dyn_eq::AnyEq;
+
+	use super::*;
+
+	#[derive(PartialEq, Eq, Debug)]
+	struct Signal {
+		value: AnyEq,
+	}
+
+	#[test]
+	fn comparisons() {
+		let a = Signal {
+			value: AnyEq::new(A { value: 1 }),
+		};
+		let b = Signal {
+			value: AnyEq::new(A { value: 1 }),
+		};
+		let c = Signal {
+			value: AnyEq::new(B { value: 1 }),
+		};
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+	}
+
+	#[test]
+	fn downcasting() {
+		let mut value = AnyEq::new(A { value: 1 });
+		assert!(value.is::<A>());
+		assert!(!value.is::<AnyEq>());
+		value.downcast_mut::<A>().unwrap().value = 2;
+		assert_eq!(value.downcast_ref::<A>(), Some(&A { value: 2 }));
+
+		let boxed = value.into_inner();
+		assert!(AnyEq::from(boxed) == AnyEq::new(A { value: 2 }));
+	}
+}
+
+#[cfg(feature = "allocator-api")]
+mod allocator_api {
+	use std::{
+		alloc::{AllocError, Allocator, Global, Layout},
+		ptr::NonNull,
+	};
+
+	use super::*;
+
+	/// An allocator that isn't the global one, to check that the impls don't require it.
+	#[derive(Clone, Copy)]
+	struct Forward;
+
+	unsafe impl Allocator for Forward {
+		fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+			Global.allocate(layout)
+		}
+
+		unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+			unsafe { Global.deallocate(ptr, layout) }
+		}
+	}
+
+	trait Message: DynEq {}
+	dyn_eq::eq_trait_object!(Message; markers(Send));
+
+	impl Message for A {}
+	impl Message for B {}
+
+	#[derive(PartialEq, Eq)]
+	struct Envelope {
+		message: Box<dyn Message, Forward>,
+	}
+
+	#[test]
+	fn derive() {
+		let a = Envelope {
+			message: Box::new_in(A { value: 1 }, Forward),
+		};
+		let b = Envelope {
+			message: Box::new_in(A { value: 1 }, Forward),
+		};
+		let c = Envelope {
+			message: Box::new_in(B { value: 1 }, Forward),
+		};
+
+		assert!(a == b);
+		assert!(a != c);
+	}
+
+	#[test]
+	fn different_allocators() {
+		let a: Box<dyn Message, Forward> = Box::new_in(A { value: 1 }, Forward);
+		let b: Box<dyn Message + Send> = Box::new(A { value: 1 });
+		let c: &dyn Message = &B { value: 1 };
+
+		assert!(a == b);
+		assert!(b == a);
+		assert!(a != *c);
+		assert!(*c != *a);
+	}
+}
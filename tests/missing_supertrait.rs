@@ -0,0 +1,25 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::process::Command;
+
+// A `compile_fail` doctest can only check the code of the error, not how many there are
+#[test]
+fn single_error() {
+	let output = Command::new(env!("CARGO"))
+		.args(["build", "--offline", "--manifest-path"])
+		.arg(concat!(
+			env!("CARGO_MANIFEST_DIR"),
+			"/tests/missing_supertrait/Cargo.toml"
+		))
+		.env("CARGO_TARGET_DIR", env!("CARGO_TARGET_TMPDIR"))
+		.output()
+		.unwrap();
+	let stderr = String::from_utf8_lossy(&output.stderr);
+
+	assert!(!output.status.success());
+	assert_eq!(stderr.matches("error[E0277]").count(), 1, "{stderr}");
+	assert_eq!(stderr.matches("error[").count(), 1, "{stderr}");
+}
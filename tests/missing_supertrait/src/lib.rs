@@ -0,0 +1,9 @@
+// Copyright (c) 2023 Zacharie Dubrulle
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Compiled by `tests/missing_supertrait.rs`, which checks that this gives a single error.
+
+pub trait NoDynEq {}
+dyn_eq::eq_trait_object!(NoDynEq);